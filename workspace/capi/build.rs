@@ -0,0 +1,17 @@
+use std::env;
+use std::path::Path;
+
+/// Set to write the generated header over the committed `include/sudoku.h`.
+const UPDATE: &str = "SUDOKU_UPDATE_HEADER";
+
+fn main() {
+    let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let bindings = cbindgen::generate(&dir).expect("Unable to generate the C header");
+    bindings.write_to_file(Path::new(&env::var("OUT_DIR").unwrap()).join("sudoku.h"));
+    if env::var_os(UPDATE).is_some() {
+        bindings.write_to_file(Path::new(&dir).join("include").join("sudoku.h"));
+    }
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed={}", UPDATE);
+}
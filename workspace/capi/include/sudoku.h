@@ -0,0 +1,161 @@
+#ifndef SUDOKU_H
+#define SUDOKU_H
+
+/* Generated by cbindgen from src/lib.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of a call.
+ */
+typedef enum SudokuStatus {
+  SUDOKU_STATUS_OK = 0,
+  /**
+   * A required pointer was `NULL`.
+   */
+  SUDOKU_STATUS_NULL_POINTER = 1,
+  /**
+   * A string was not valid UTF-8 or did not name a known format.
+   */
+  SUDOKU_STATUS_INVALID_ARGUMENT = 2,
+  /**
+   * A puzzle could not be read.
+   */
+  SUDOKU_STATUS_PARSE_ERROR = 3,
+  /**
+   * A cell or digit was outside of the board.
+   */
+  SUDOKU_STATUS_OUT_OF_RANGE = 4,
+  /**
+   * A digit breaks the rules or a given would be changed.
+   */
+  SUDOKU_STATUS_REFUSED = 5,
+  /**
+   * The puzzle has no solution.
+   */
+  SUDOKU_STATUS_NO_SOLUTION = 6,
+  /**
+   * An unexpected internal error.
+   */
+  SUDOKU_STATUS_PANIC = 7,
+} SudokuStatus;
+
+/**
+ * Opaque handle of a board.
+ */
+typedef struct SudokuBoard SudokuBoard;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Reads a puzzle in any supported format, detected from its content, into a
+ * new board released with `sudoku_board_free`.
+ *
+ * # Safety
+ *
+ * `text` must be a NUL-terminated string and `out` a writable pointer.
+ */
+enum SudokuStatus sudoku_board_parse(const char *text, struct SudokuBoard **out);
+
+/**
+ * Releases a board, doing nothing for `NULL`.
+ *
+ * # Safety
+ *
+ * `board` must come from this library and not be used afterwards.
+ */
+void sudoku_board_free(struct SudokuBoard *board);
+
+/**
+ * Writes the number of rows of the board, which is also its highest digit.
+ *
+ * # Safety
+ *
+ * `board` must be a valid handle and `out` a writable pointer.
+ */
+enum SudokuStatus sudoku_board_top(const struct SudokuBoard *board, uint16_t *out);
+
+/**
+ * Writes the digit of a cell, counted from 1, or 0 for an empty cell.
+ *
+ * # Safety
+ *
+ * `board` must be a valid handle and `out` a writable pointer.
+ */
+enum SudokuStatus sudoku_board_get(const struct SudokuBoard *board,
+                                   uint16_t row,
+                                   uint16_t col,
+                                   uint16_t *out);
+
+/**
+ * Places a digit following the rules, or clears the cell for 0.
+ *
+ * # Safety
+ *
+ * `board` must be a valid handle.
+ */
+enum SudokuStatus sudoku_board_set(struct SudokuBoard *board,
+                                   uint16_t row,
+                                   uint16_t col,
+                                   uint16_t value);
+
+/**
+ * Solves the board into a new one released with `sudoku_board_free`.
+ *
+ * # Safety
+ *
+ * `board` must be a valid handle and `out` a writable pointer.
+ */
+enum SudokuStatus sudoku_board_solve(const struct SudokuBoard *board, struct SudokuBoard **out);
+
+/**
+ * Counts solutions of the board, stopping once `limit` of them are found.
+ *
+ * # Safety
+ *
+ * `board` must be a valid handle and `out` a writable pointer.
+ */
+enum SudokuStatus sudoku_board_count_solutions(const struct SudokuBoard *board,
+                                               size_t limit,
+                                               size_t *out);
+
+/**
+ * Renders the board as text in a named puzzle format, such as `line` or
+ * `sdk`, or as `svg`. `NULL` draws it with box characters. The string is
+ * released with `sudoku_string_free`.
+ *
+ * # Safety
+ *
+ * `board` must be a valid handle, `format` `NULL` or a NUL-terminated string
+ * and `out` a writable pointer.
+ */
+enum SudokuStatus sudoku_board_render(const struct SudokuBoard *board,
+                                      const char *format,
+                                      char **out);
+
+/**
+ * Releases a string returned by this library, doing nothing for `NULL`.
+ *
+ * # Safety
+ *
+ * `string` must come from this library and not be used afterwards.
+ */
+void sudoku_string_free(char *string);
+
+/**
+ * Describes the last failure on the calling thread, or returns `NULL`. The
+ * string stays valid until the next failing call on the thread.
+ */
+const char *sudoku_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SUDOKU_H */
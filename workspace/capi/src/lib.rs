@@ -0,0 +1,419 @@
+//! C API of `libsudoku`, declared in the generated `include/sudoku.h`.
+//!
+//! Builds generate the header in `OUT_DIR` only. Set `SUDOKU_UPDATE_HEADER`
+//! to refresh the committed copy after changing the API.
+//!
+//! Boards are passed around as opaque handles. Every call returns a
+//! `SudokuStatus`, with a description of the last failure on the calling
+//! thread available from `sudoku_last_error`. Panics never cross the API.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use libsudoku::formats::{Format, Puzzle};
+use libsudoku::loc::{Loc, L};
+use libsudoku::svg::SvgOptions;
+use libsudoku::Board;
+
+/// Outcome of a call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SudokuStatus {
+    Ok = 0,
+    /// A required pointer was `NULL`.
+    NullPointer = 1,
+    /// A string was not valid UTF-8 or did not name a known format.
+    InvalidArgument = 2,
+    /// A puzzle could not be read.
+    ParseError = 3,
+    /// A cell or digit was outside of the board.
+    OutOfRange = 4,
+    /// A digit breaks the rules or a given would be changed.
+    Refused = 5,
+    /// The puzzle has no solution.
+    NoSolution = 6,
+    /// An unexpected internal error.
+    Panic = 7,
+}
+
+/// Opaque handle of a board.
+pub struct SudokuBoard {
+    board: Board,
+}
+
+type Error = (SudokuStatus, String);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Runs `body`, turning its errors and panics into a status and remembering
+/// their messages for `sudoku_last_error`.
+fn call(body: impl FnOnce() -> Result<(), Error>) -> SudokuStatus {
+    let (status, message) = match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => return SudokuStatus::Ok,
+        Ok(Err(error)) => error,
+        Err(_) => (SudokuStatus::Panic, "Unexpected panic".to_string()),
+    };
+    let message = CString::new(message.replace('\0', " ")).ok();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+fn null(name: &str) -> Error {
+    (SudokuStatus::NullPointer, format!("'{}' is NULL", name))
+}
+
+unsafe fn text<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, Error> {
+    if ptr.is_null() {
+        return Err(null(name));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|e| (SudokuStatus::InvalidArgument, format!("'{}': {}", name, e)))
+}
+
+unsafe fn board<'a>(ptr: *const SudokuBoard) -> Result<&'a Board, Error> {
+    ptr.as_ref().map(|b| &b.board).ok_or(null("board"))
+}
+
+unsafe fn write<T>(out: *mut T, value: T, name: &str) -> Result<(), Error> {
+    if out.is_null() {
+        return Err(null(name));
+    }
+    *out = value;
+    Ok(())
+}
+
+fn loc(board: &Board, row: u16, col: u16) -> Result<Loc, Error> {
+    let loc = Loc::new(row, col);
+    match board.contains_key(&loc) {
+        true => Ok(loc),
+        false => Err((
+            SudokuStatus::OutOfRange,
+            format!("Cell r{}c{} is outside of the board", row, col),
+        )),
+    }
+}
+
+fn handle(board: Board) -> *mut SudokuBoard {
+    Box::into_raw(Box::new(SudokuBoard { board }))
+}
+
+/// Reads a puzzle in any supported format, detected from its content, into a
+/// new board released with `sudoku_board_free`.
+///
+/// # Safety
+///
+/// `text` must be a NUL-terminated string and `out` a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_board_parse(
+    text: *const c_char,
+    out: *mut *mut SudokuBoard,
+) -> SudokuStatus {
+    call(|| {
+        let text = self::text(text, "text")?;
+        let puzzle = Puzzle::read(text).map_err(|e| (SudokuStatus::ParseError, e))?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        write(out, handle(puzzle.state.unwrap_or(puzzle.board)), "out")
+    })
+}
+
+/// Releases a board, doing nothing for `NULL`.
+///
+/// # Safety
+///
+/// `board` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_board_free(board: *mut SudokuBoard) {
+    if !board.is_null() {
+        drop(Box::from_raw(board));
+    }
+}
+
+/// Writes the number of rows of the board, which is also its highest digit.
+///
+/// # Safety
+///
+/// `board` must be a valid handle and `out` a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_board_top(
+    board: *const SudokuBoard,
+    out: *mut u16,
+) -> SudokuStatus {
+    call(|| write(out, self::board(board)?.top(), "out"))
+}
+
+/// Writes the digit of a cell, counted from 1, or 0 for an empty cell.
+///
+/// # Safety
+///
+/// `board` must be a valid handle and `out` a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_board_get(
+    board: *const SudokuBoard,
+    row: u16,
+    col: u16,
+    out: *mut u16,
+) -> SudokuStatus {
+    call(|| {
+        let board = self::board(board)?;
+        let loc = loc(board, row, col)?;
+        write(out, board.value(&loc).unwrap_or(0), "out")
+    })
+}
+
+/// Places a digit following the rules, or clears the cell for 0.
+///
+/// # Safety
+///
+/// `board` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_board_set(
+    board: *mut SudokuBoard,
+    row: u16,
+    col: u16,
+    value: u16,
+) -> SudokuStatus {
+    call(|| {
+        let board = &mut board.as_mut().ok_or(null("board"))?.board;
+        let loc = loc(board, row, col)?;
+        if value > board.top() {
+            return Err((
+                SudokuStatus::OutOfRange,
+                format!("Digit {} is outside of the board", value),
+            ));
+        }
+        let value = (value > 0).then_some(value as L);
+        board
+            .try_set(&loc, value)
+            .map_err(|e| (SudokuStatus::Refused, e))
+    })
+}
+
+/// Solves the board into a new one released with `sudoku_board_free`.
+///
+/// # Safety
+///
+/// `board` must be a valid handle and `out` a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_board_solve(
+    board: *const SudokuBoard,
+    out: *mut *mut SudokuBoard,
+) -> SudokuStatus {
+    call(|| {
+        let solution = self::board(board)?.solve().ok_or((
+            SudokuStatus::NoSolution,
+            "Puzzle has no solution".to_string(),
+        ))?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        write(out, handle(solution), "out")
+    })
+}
+
+/// Counts solutions of the board, stopping once `limit` of them are found.
+///
+/// # Safety
+///
+/// `board` must be a valid handle and `out` a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_board_count_solutions(
+    board: *const SudokuBoard,
+    limit: usize,
+    out: *mut usize,
+) -> SudokuStatus {
+    call(|| write(out, self::board(board)?.count_solutions(limit), "out"))
+}
+
+/// Renders the board as text in a named puzzle format, such as `line` or
+/// `sdk`, or as `svg`. `NULL` draws it with box characters. The string is
+/// released with `sudoku_string_free`.
+///
+/// # Safety
+///
+/// `board` must be a valid handle, `format` `NULL` or a NUL-terminated string
+/// and `out` a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_board_render(
+    board: *const SudokuBoard,
+    format: *const c_char,
+    out: *mut *mut c_char,
+) -> SudokuStatus {
+    call(|| {
+        let board = self::board(board)?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let rendered = match format.is_null() {
+            true => board.draw(),
+            false => match text(format, "format")? {
+                "svg" => board.to_svg(&SvgOptions::default()),
+                name => {
+                    let format: Format = name
+                        .parse()
+                        .map_err(|e| (SudokuStatus::InvalidArgument, e))?;
+                    Puzzle::from(board.clone())
+                        .write(format)
+                        .map_err(|e| (SudokuStatus::InvalidArgument, e))?
+                }
+            },
+        };
+        let rendered = CString::new(rendered).map_err(|e| (SudokuStatus::Panic, e.to_string()))?;
+        write(out, rendered.into_raw(), "out")
+    })
+}
+
+/// Releases a string returned by this library, doing nothing for `NULL`.
+///
+/// # Safety
+///
+/// `string` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Describes the last failure on the calling thread, or returns `NULL`. The
+/// string stays valid until the next failing call on the thread.
+#[no_mangle]
+pub extern "C" fn sudoku_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_is_up_to_date() {
+        assert!(
+            include_str!(concat!(env!("OUT_DIR"), "/sudoku.h"))
+                == include_str!("../include/sudoku.h"),
+            "include/sudoku.h is stale, rebuild with SUDOKU_UPDATE_HEADER=1"
+        );
+    }
+
+    fn parse(text: &str) -> *mut SudokuBoard {
+        let text = CString::new(text).unwrap();
+        let mut board = ptr::null_mut();
+        assert_eq!(
+            unsafe { sudoku_board_parse(text.as_ptr(), &mut board) },
+            SudokuStatus::Ok
+        );
+        board
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(sudoku_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_board_calls() {
+        let board = parse("1.3434.22143.321");
+        let mut value = 9;
+        unsafe {
+            assert_eq!(sudoku_board_top(board, &mut value), SudokuStatus::Ok);
+            assert_eq!(value, 4);
+            assert_eq!(sudoku_board_get(board, 1, 2, &mut value), SudokuStatus::Ok);
+            assert_eq!(value, 0);
+            assert_eq!(sudoku_board_set(board, 1, 2, 2), SudokuStatus::Ok);
+            assert_eq!(sudoku_board_get(board, 1, 2, &mut value), SudokuStatus::Ok);
+            assert_eq!(value, 2);
+
+            let mut count = 0;
+            assert_eq!(
+                sudoku_board_count_solutions(board, 10, &mut count),
+                SudokuStatus::Ok
+            );
+            assert_eq!(count, 1);
+
+            let mut solution = ptr::null_mut();
+            assert_eq!(sudoku_board_solve(board, &mut solution), SudokuStatus::Ok);
+            let mut text = ptr::null_mut();
+            let format = CString::new("line").unwrap();
+            assert_eq!(
+                sudoku_board_render(solution, format.as_ptr(), &mut text),
+                SudokuStatus::Ok
+            );
+            assert_eq!(CStr::from_ptr(text).to_str(), Ok("1234341221434321\n"));
+            sudoku_string_free(text);
+            assert_eq!(
+                sudoku_board_render(board, ptr::null(), &mut text),
+                SudokuStatus::Ok
+            );
+            assert!(CStr::from_ptr(text).to_str().unwrap().starts_with("╔"));
+            sudoku_string_free(text);
+
+            sudoku_board_free(solution);
+            sudoku_board_free(board);
+        }
+    }
+
+    #[test]
+    fn test_board_errors() {
+        let board = parse("1.3434.22143.321");
+        let mut value = 0;
+        let mut other = ptr::null_mut();
+        unsafe {
+            let text = CString::new("1").unwrap();
+            assert_eq!(
+                sudoku_board_parse(text.as_ptr(), ptr::null_mut()),
+                SudokuStatus::NullPointer
+            );
+            let text = CString::new("12").unwrap();
+            assert_eq!(
+                sudoku_board_parse(text.as_ptr(), &mut other),
+                SudokuStatus::ParseError
+            );
+            assert!(other.is_null());
+            let text = b"\xff\0";
+            assert_eq!(
+                sudoku_board_parse(text.as_ptr() as *const c_char, &mut other),
+                SudokuStatus::InvalidArgument
+            );
+            assert_eq!(
+                sudoku_board_top(ptr::null(), &mut value),
+                SudokuStatus::NullPointer
+            );
+            assert_eq!(last_error(), "'board' is NULL");
+            assert_eq!(
+                sudoku_board_get(board, 5, 1, &mut value),
+                SudokuStatus::OutOfRange
+            );
+            assert_eq!(sudoku_board_set(board, 1, 2, 5), SudokuStatus::OutOfRange);
+            assert_eq!(sudoku_board_set(board, 1, 2, 4), SudokuStatus::Refused);
+            assert_eq!(sudoku_board_set(board, 1, 1, 0), SudokuStatus::Refused);
+            assert!(last_error().contains("given"), "{}", last_error());
+            let format = CString::new("pdf").unwrap();
+            let mut text = ptr::null_mut();
+            assert_eq!(
+                sudoku_board_render(board, format.as_ptr(), &mut text),
+                SudokuStatus::InvalidArgument
+            );
+            sudoku_board_free(board);
+
+            let board = parse("11..............");
+            assert_eq!(
+                sudoku_board_solve(board, &mut other),
+                SudokuStatus::NoSolution
+            );
+            sudoku_board_free(board);
+            sudoku_board_free(ptr::null_mut());
+        }
+    }
+}
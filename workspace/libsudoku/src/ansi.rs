@@ -0,0 +1,213 @@
+use alloc::collections::BTreeSet;
+#[cfg(feature = "std")]
+use std::io::IsTerminal;
+
+use super::loc::{Loc, L};
+use super::prelude::*;
+use super::{Board, Origin};
+
+const RESET: &str = "\x1b[0m";
+const GIVEN: &str = "\x1b[1m";
+const ENTERED: &str = "\x1b[36m";
+const SOLVED: &str = "\x1b[32m";
+const CONFLICT: &str = "\x1b[1;41;97m";
+const SELECTED: &str = "\x1b[7m";
+const PEER: &str = "\x1b[48;5;237m";
+const CANDIDATE: &str = "\x1b[2m";
+
+/// Settings of `Board::draw_ansi`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnsiOptions {
+    /// Whether escape codes are written at all, see `AnsiOptions::for_stdout`.
+    pub color: bool,
+    /// Whether cells breaking the rules are highlighted.
+    pub conflicts: bool,
+    /// Cell marked along with its peers.
+    pub selected: Option<Loc>,
+    /// Whether candidates of empty cells are drawn as small digits.
+    pub candidates: bool,
+}
+
+impl AnsiOptions {
+    /// Enables colors only when stdout is a terminal and `NO_COLOR` is not set.
+    #[cfg(feature = "std")]
+    pub fn for_stdout() -> Self {
+        Self {
+            color: std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            ..Self::default()
+        }
+    }
+}
+
+impl Board {
+    /// Draws the board like `Board::draw`, with givens in bold, digits entered
+    /// by the player in cyan and solved ones in green, and optional highlights
+    /// of conflicts and the selected cell.
+    ///
+    /// With candidates enabled every cell takes a square of `size` lines, with
+    /// the candidates of empty cells at fixed positions.
+    pub fn draw_ansi(&self, options: &AnsiOptions) -> String {
+        let symbol = self.cell_width();
+        let size = usize::from(self.size);
+        let (width, height) = match options.candidates {
+            true => (size * (symbol + 1) - 1, size),
+            false => (symbol, 1),
+        };
+        let conflicts: BTreeSet<Loc> = match options.conflicts {
+            true => self.conflicts().into_iter().collect(),
+            false => BTreeSet::new(),
+        };
+        let peers: BTreeSet<Loc> = options
+            .selected
+            .map(|loc| self.peers(&loc).into_iter().collect())
+            .unwrap_or_default();
+        let paint = |style: &str, text: String| match options.color && !style.is_empty() {
+            true => format!("{}{}{}", style, text, RESET),
+            false => text,
+        };
+
+        let mut output = Board::draw_upper_bar(self.size, width);
+        for row in 1..=self.top {
+            let mut lines = vec![String::from("║"); height];
+            for col in 1..=self.top {
+                let loc = Loc::new(row, col);
+                let background = if options.selected == Some(loc) {
+                    SELECTED
+                } else if conflicts.contains(&loc) {
+                    CONFLICT
+                } else if peers.contains(&loc) {
+                    PEER
+                } else {
+                    ""
+                };
+                let cell = self.ansi_cell(&loc, options, width, height);
+                for (line, (style, text)) in lines.iter_mut().zip(cell) {
+                    let text = format!(" {} ", text);
+                    let text = match (background, style) {
+                        ("", style) => paint(style, text),
+                        (background, style) => paint(&format!("{}{}", background, style), text),
+                    };
+                    line.push_str(&text);
+                    line.push(if col % self.size == 0 { '║' } else { '│' });
+                }
+            }
+            for line in lines {
+                output.push_str(&line);
+                output.push('\n');
+            }
+            if row < self.top {
+                if row % self.size == 0 {
+                    output.push_str(&Board::draw_middle_bar(self.size, width));
+                } else {
+                    output.push_str(&Board::draw_middle_soft(self.size, width));
+                }
+            }
+        }
+        output.push_str(&Board::draw_lower_bar(self.size, width));
+        output
+    }
+
+    /// Lines of a single cell along with their styles, padded to `width`.
+    fn ansi_cell(
+        &self,
+        loc: &Loc,
+        options: &AnsiOptions,
+        width: usize,
+        height: usize,
+    ) -> Vec<(&'static str, String)> {
+        let blank = " ".repeat(width);
+        if let Some(v) = self.value(loc) {
+            let style = match self.origin(loc) {
+                Some(Origin::Entered) => ENTERED,
+                Some(Origin::Solved) => SOLVED,
+                _ => GIVEN,
+            };
+            let symbol = self.symbols.symbol(v).unwrap_or("?");
+            let mut lines = vec![("", blank.clone()); height];
+            lines[height / 2] = (style, format!("{:^width$}", symbol, width = width));
+            return lines;
+        }
+        if !options.candidates {
+            return vec![("", format!("{:>width$}", "■", width = width))];
+        }
+
+        let symbol = self.cell_width();
+        let candidates = self.candidates(loc);
+        let size = usize::from(self.size);
+        (0..size)
+            .map(|r| {
+                let marks: Vec<String> = (0..size)
+                    .map(|c| {
+                        let value = (r * size + c + 1) as L;
+                        match candidates.contains(&value) {
+                            true => format!(
+                                "{:>symbol$}",
+                                self.symbols.symbol(value).unwrap_or("?"),
+                                symbol = symbol
+                            ),
+                            false => " ".repeat(symbol),
+                        }
+                    })
+                    .collect();
+                (CANDIDATE, marks.join(" "))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_draw_ansi_plain() {
+        let board = Board::from_line("1.3434.22143.321").unwrap();
+        let options = AnsiOptions {
+            conflicts: true,
+            selected: Some(Loc::new(1, 1)),
+            ..AnsiOptions::default()
+        };
+        assert_eq!(board.draw_ansi(&options), board.draw());
+    }
+
+    #[test]
+    fn test_board_draw_ansi_colors() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        board.set(&Loc::new(1, 2), Some(2)).unwrap();
+        let options = AnsiOptions {
+            color: true,
+            ..AnsiOptions::default()
+        };
+        let output = board.draw_ansi(&options);
+        assert_eq!(output.matches(GIVEN).count(), 13);
+        assert!(output.starts_with("╔═══════╦═══════╗\n║\x1b[1m 1 \x1b[0m│\x1b[36m 2 \x1b[0m║"));
+        let output = board.solve().unwrap().draw_ansi(&options);
+        assert_eq!(output.matches(SOLVED).count(), 2);
+
+        board.set(&Loc::new(2, 3), Some(4)).unwrap();
+        let output = board.draw_ansi(&AnsiOptions {
+            conflicts: true,
+            selected: Some(Loc::new(4, 4)),
+            ..options
+        });
+        assert_eq!(output.matches(CONFLICT).count(), 4);
+        assert_eq!(output.matches(SELECTED).count(), 1);
+        assert_eq!(output.matches(PEER).count(), 5);
+    }
+
+    #[test]
+    fn test_board_draw_ansi_candidates() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        board.set_marks(&Loc::new(1, 2), Some(vec![2, 4]));
+        let output = board.draw_ansi(&AnsiOptions {
+            candidates: true,
+            ..AnsiOptions::default()
+        });
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4 * 2 + 5);
+        assert_eq!(lines[0], "╔═══════════╦═══════════╗");
+        assert_eq!(lines[1], "║     │   2 ║     │     ║");
+        assert_eq!(lines[2], "║  1  │     ║  3  │  4  ║");
+        assert_eq!(lines[4], "║     │     ║ 1   │     ║");
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::formats::Puzzle;
+use super::prelude::*;
+use super::Board;
+
+/// Puzzles read ahead for every worker thread.
+const QUEUE_PER_THREAD: usize = 16;
+
+/// Result of solving a single puzzle of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Status {
+    /// The puzzle has exactly one solution.
+    Solved,
+    /// The puzzle has no solution.
+    Unsolvable,
+    /// The puzzle has more than one solution.
+    Multiple,
+    /// The line could not be read as a puzzle.
+    Invalid,
+}
+
+impl Status {
+    pub const ALL: [Status; 4] = [
+        Status::Solved,
+        Status::Unsolvable,
+        Status::Multiple,
+        Status::Invalid,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Solved => "solved",
+            Status::Unsolvable => "unsolvable",
+            Status::Multiple => "multiple",
+            Status::Invalid => "invalid",
+        }
+    }
+}
+
+/// Puzzle of a batch along with its status.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    /// Line of the puzzle in the input, counted from 1.
+    pub line: usize,
+    pub status: Status,
+    /// Solution of a puzzle with `Status::Solved`.
+    pub solution: Option<Board>,
+    /// Why a puzzle with `Status::Invalid` could not be read.
+    pub error: Option<String>,
+    /// Time spent reading and solving the puzzle.
+    pub elapsed: Duration,
+}
+
+/// Reads a puzzle in any supported format and solves it, reporting parse
+/// errors as invalid puzzles.
+pub fn solve_line(line: usize, text: &str) -> Outcome {
+    let start = Instant::now();
+    let (status, solution, error) = match Puzzle::read(text) {
+        Ok(puzzle) => match puzzle.board.solutions(2) {
+            (0, _) => (Status::Unsolvable, None, None),
+            (1, solution) => (Status::Solved, solution, None),
+            _ => (Status::Multiple, None, None),
+        },
+        Err(e) => (Status::Invalid, None, Some(e)),
+    };
+    Outcome {
+        line,
+        status,
+        solution,
+        error,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Solves puzzles read one per line on several threads, yielding their
+/// outcomes in input order. Blank lines are skipped and lines that are not
+/// valid UTF-8 are reported as invalid; only errors of the reader itself end
+/// the batch.
+pub struct Batch<R> {
+    reader: R,
+    line: usize,
+    jobs: Option<SyncSender<(usize, String)>>,
+    results: Receiver<Outcome>,
+    queued: VecDeque<usize>,
+    done: BTreeMap<usize, Outcome>,
+    capacity: usize,
+    error: Option<io::Error>,
+}
+
+impl<R: BufRead> Batch<R> {
+    /// Starts `threads` workers, or one per CPU core for 0.
+    pub fn new(reader: R, threads: usize) -> Self {
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, usize::from),
+            n => n,
+        };
+        let capacity = threads * QUEUE_PER_THREAD;
+        let (jobs, queue) = mpsc::sync_channel::<(usize, String)>(capacity);
+        let (sender, results) = mpsc::channel();
+        let queue = Arc::new(Mutex::new(queue));
+        for _ in 0..threads {
+            let (queue, sender) = (Arc::clone(&queue), sender.clone());
+            thread::spawn(move || loop {
+                let job = queue
+                    .lock()
+                    .map_err(|_| ())
+                    .and_then(|q| q.recv().map_err(|_| ()));
+                match job {
+                    Ok((line, text)) if sender.send(solve_line(line, &text)).is_ok() => {}
+                    _ => break,
+                }
+            });
+        }
+        Self {
+            reader,
+            line: 0,
+            jobs: Some(jobs),
+            results,
+            queued: VecDeque::new(),
+            done: BTreeMap::new(),
+            capacity,
+            error: None,
+        }
+    }
+
+    /// Reads the next non-blank line, returning `None` at the end of input.
+    fn read_line(&mut self) -> io::Result<Option<(usize, String)>> {
+        let mut bytes = Vec::new();
+        loop {
+            bytes.clear();
+            if self.reader.read_until(b'\n', &mut bytes)? == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            let text = String::from_utf8_lossy(&bytes);
+            if !text.trim().is_empty() {
+                return Ok(Some((self.line, text.into_owned())));
+            }
+        }
+    }
+
+    /// Keeps the workers busy until the input runs out or fails.
+    fn feed(&mut self) {
+        while self.jobs.is_some() && self.queued.len() < self.capacity {
+            match self.read_line() {
+                Ok(Some((line, text))) => {
+                    self.queued.push_back(line);
+                    let jobs = self.jobs.as_ref();
+                    if jobs.is_none_or(|jobs| jobs.send((line, text)).is_err()) {
+                        self.jobs = None;
+                    }
+                }
+                Ok(None) => self.jobs = None,
+                Err(e) => {
+                    self.error = Some(e);
+                    self.jobs = None;
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Batch<R> {
+    type Item = io::Result<Outcome>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.feed();
+        let Some(next) = self.queued.front().copied() else {
+            return self.error.take().map(Err);
+        };
+        loop {
+            if let Some(outcome) = self.done.remove(&next) {
+                self.queued.pop_front();
+                return Some(Ok(outcome));
+            }
+            match self.results.recv() {
+                Ok(outcome) => {
+                    self.done.insert(outcome.line, outcome);
+                }
+                Err(_) => {
+                    let error = io::Error::other("Batch workers stopped unexpectedly");
+                    self.queued.clear();
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_line() {
+        let outcome = solve_line(3, "1.3434.22143.321");
+        assert_eq!((outcome.line, outcome.status), (3, Status::Solved));
+        assert_eq!(
+            outcome.solution.and_then(|s| s.to_line().ok()),
+            Some("1234341221434321".to_string())
+        );
+
+        assert_eq!(solve_line(1, "11..............").status, Status::Unsolvable);
+        assert_eq!(solve_line(1, "................").status, Status::Multiple);
+        let outcome = solve_line(1, "12");
+        assert_eq!(outcome.status, Status::Invalid);
+        assert!(outcome.error.is_some());
+        for text in [
+            "[Puzzle]",
+            "[[1,",
+            "[[0]]",
+            "| 1 2 |",
+            "1 u 2 3",
+            "╔═╗",
+            "#A x",
+            "*---*",
+        ] {
+            assert_eq!(solve_line(1, text).status, Status::Invalid, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_batch() {
+        let mut input = Vec::new();
+        for i in 0..100 {
+            let line: &[u8] = match i % 4 {
+                0 => b"1.3434.22143.321\n",
+                1 => b"11..............\n",
+                2 => b"\n",
+                _ => b"\xff\xfe\n",
+            };
+            input.extend_from_slice(line);
+        }
+        input.extend_from_slice(b"1");
+
+        let outcomes: Vec<Outcome> = Batch::new(&input[..], 4)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(outcomes.len(), 76);
+        assert!(outcomes.windows(2).all(|w| w[0].line < w[1].line));
+        for outcome in outcomes.iter().take(75) {
+            let expected = match outcome.line % 4 {
+                1 => Status::Solved,
+                2 => Status::Unsolvable,
+                _ => Status::Invalid,
+            };
+            assert_eq!(outcome.status, expected, "line {}", outcome.line);
+        }
+        assert_eq!(outcomes[75].line, 101);
+        assert_eq!(outcomes[75].status, Status::Solved);
+    }
+}
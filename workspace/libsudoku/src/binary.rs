@@ -0,0 +1,401 @@
+use std::io::{self, Read, Write};
+
+use super::loc::L;
+use super::prelude::*;
+use super::{Board, Origin};
+
+/// Version written into the header of every record.
+pub const VERSION: u8 = 1;
+
+/// Bytes opening a stream of records written by `RecordWriter`.
+pub const MAGIC: [u8; 4] = *b"SDKB";
+
+/// Largest box size of a record, a 256x256 grid.
+pub const MAX_SIZE: u8 = 16;
+
+/// Kind of a binary record, stored in its header after the version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Bitmap of the clues followed by their digits, packed in as few bits
+    /// as the board size allows.
+    Puzzle = 0,
+    /// Complete grid stored as its rank among the grids reachable by filling
+    /// cells in order, with a two-byte length prefix.
+    Solution = 1,
+}
+
+impl TryFrom<u8> for Kind {
+    type Error = String;
+
+    fn try_from(other: u8) -> Result<Self, Self::Error> {
+        match other {
+            0 => Ok(Kind::Puzzle),
+            1 => Ok(Kind::Solution),
+            _ => Err(format!("Unknown record kind {}", other)),
+        }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Number of bits needed to store `value - 1` for every value of the board.
+fn digit_bits(top: L) -> usize {
+    (L::BITS - (top - 1).leading_zeros()) as usize
+}
+
+/// Multiplies a little-endian big number by `radix` and adds `digit`.
+fn mul_add(number: &mut Vec<u32>, radix: u32, digit: u32) {
+    let mut carry = u64::from(digit);
+    for limb in number.iter_mut() {
+        let value = u64::from(*limb) * u64::from(radix) + carry;
+        *limb = value as u32;
+        carry = value >> 32;
+    }
+    if carry > 0 {
+        number.push(carry as u32);
+    }
+}
+
+/// Divides a little-endian big number by `radix` in place, returning the remainder.
+fn div_rem(number: &mut [u32], radix: u32) -> u32 {
+    let mut remainder = 0u64;
+    for limb in number.iter_mut().rev() {
+        let value = (remainder << 32) | u64::from(*limb);
+        *limb = (value / u64::from(radix)) as u32;
+        remainder = value % u64::from(radix);
+    }
+    remainder as u32
+}
+
+impl Board {
+    fn binary_header(&self, kind: Kind) -> Result<Vec<u8>, String> {
+        match u8::try_from(self.size) {
+            Ok(size) if size <= MAX_SIZE => Ok(vec![VERSION, kind as u8, size]),
+            _ => Err(format!(
+                "Board of size {} is too large to encode",
+                self.size
+            )),
+        }
+    }
+
+    /// Encodes the givens of the board as a puzzle record, failing if the box
+    /// size exceeds `MAX_SIZE`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut output = self.binary_header(Kind::Puzzle)?;
+        let locs = Board::gen_all_locs(self.top);
+        let mut bitmap = vec![0u8; locs.len().div_ceil(8)];
+        let mut digits = BitWriter::default();
+        let bits = digit_bits(self.top);
+        for (i, loc) in locs.iter().enumerate() {
+            if let Some(v) = self.value(loc) {
+                bitmap[i / 8] |= 1 << (i % 8);
+                digits.push(u32::from(v - 1), bits);
+            }
+        }
+        output.extend(bitmap);
+        output.extend(digits.bytes);
+        Ok(output)
+    }
+
+    /// Encodes a complete and valid grid as a solution record.
+    ///
+    /// Every cell is stored as the index of its digit among the values still
+    /// available after filling the previous cells, so forced cells take no
+    /// space at all and a 9x9 grid fits in eleven bytes.
+    pub fn to_solution_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut output = self.binary_header(Kind::Solution)?;
+        let mut board = Board::new(self.size);
+        let mut digits = Vec::with_capacity(usize::from(self.top).pow(2));
+        for loc in Board::gen_all_locs(self.top) {
+            let value = self
+                .value(&loc)
+                .ok_or(format!("Solution is missing a value in {}", loc))?;
+            let available = board.available_values(&loc);
+            let index = available
+                .iter()
+                .position(|v| *v == value)
+                .ok_or(format!("Value {} in {} breaks the rules", value, loc))?;
+            digits.push((available.len() as u32, index as u32));
+            board.force_set(&loc, Some(value), Origin::Given);
+        }
+
+        let mut rank = Vec::new();
+        for (radix, digit) in digits.into_iter().rev() {
+            mul_add(&mut rank, radix, digit);
+        }
+        let mut bytes: Vec<u8> = rank.iter().flat_map(|l| l.to_le_bytes()).collect();
+        while bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        let length = u16::try_from(bytes.len()).map_err(|e| e.to_string())?;
+        output.extend(length.to_le_bytes());
+        output.extend(bytes);
+        Ok(output)
+    }
+
+    /// Decodes a single puzzle or solution record.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = bytes;
+        let board = read_record(&mut reader)
+            .map_err(|e| e.to_string())?
+            .ok_or("No record to decode")?;
+        match reader.is_empty() {
+            true => Ok(board),
+            false => Err(format!("{} bytes left after the record", reader.len())),
+        }
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    used: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: u32, bits: usize) {
+        for bit in 0..bits {
+            if self.used.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> bit & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 1 << (self.used % 8);
+            }
+            self.used += 1;
+        }
+    }
+}
+
+fn read_bits(bytes: &[u8], offset: usize, bits: usize) -> u32 {
+    (0..bits)
+        .filter(|b| bytes[(offset + b) / 8] >> ((offset + b) % 8) & 1 == 1)
+        .map(|b| 1 << b)
+        .sum()
+}
+
+/// Reads one record, returning `None` at the end of the input.
+fn read_record<R: Read>(reader: &mut R) -> io::Result<Option<Board>> {
+    let mut header = [0u8; 3];
+    match reader.read(&mut header[..1])? {
+        0 => return Ok(None),
+        _ => reader.read_exact(&mut header[1..])?,
+    }
+    let [version, kind, size] = header;
+    if version != VERSION {
+        return Err(invalid(format!("Unsupported record version {}", version)));
+    }
+    if size == 0 {
+        return Err(invalid("Record of an empty board".to_string()));
+    }
+    if size > MAX_SIZE {
+        return Err(invalid(format!("Record of a board of size {}", size)));
+    }
+    let kind = Kind::try_from(kind).map_err(invalid)?;
+    let top = L::from(size).pow(2);
+    let locs = Board::gen_all_locs(top);
+
+    // The payload is read before the board is built, so a truncated record
+    // fails without allocating a grid for it.
+    match kind {
+        Kind::Puzzle => {
+            let mut bitmap = vec![0u8; locs.len().div_ceil(8)];
+            reader.read_exact(&mut bitmap)?;
+            let clues = bitmap
+                .iter()
+                .map(|b| b.count_ones() as usize)
+                .sum::<usize>();
+            let bits = digit_bits(top);
+            let mut digits = vec![0u8; (clues * bits).div_ceil(8)];
+            reader.read_exact(&mut digits)?;
+
+            let mut board = Board::new(L::from(size));
+            let mut offset = 0;
+            for (i, loc) in locs.iter().enumerate() {
+                if bitmap[i / 8] >> (i % 8) & 1 == 1 {
+                    let value = read_bits(&digits, offset, bits) + 1;
+                    if value > u32::from(board.top) {
+                        return Err(invalid(format!("Invalid value {} in {}", value, loc)));
+                    }
+                    board.force_set(loc, Some(value as L), Origin::Given);
+                    offset += bits;
+                }
+            }
+            Ok(Some(board))
+        }
+        Kind::Solution => {
+            let mut length = [0u8; 2];
+            reader.read_exact(&mut length)?;
+            let mut bytes = vec![0u8; usize::from(u16::from_le_bytes(length))];
+            reader.read_exact(&mut bytes)?;
+            let mut rank: Vec<u32> = bytes
+                .chunks(4)
+                .map(|c| c.iter().rev().fold(0, |n, b| n << 8 | u32::from(*b)))
+                .collect();
+
+            let mut board = Board::new(L::from(size));
+            for loc in locs {
+                let available = board.available_values(&loc);
+                if available.is_empty() {
+                    return Err(invalid(format!("No value left for {}", loc)));
+                }
+                let index = div_rem(&mut rank, available.len() as u32);
+                board.force_set(&loc, Some(available[index as usize]), Origin::Given);
+            }
+            if rank.iter().any(|l| *l != 0) {
+                return Err(invalid("Solution rank is out of range".to_string()));
+            }
+            Ok(Some(board))
+        }
+    }
+}
+
+/// Writes puzzles and solutions as a stream of binary records, starting with
+/// `MAGIC`.
+pub struct RecordWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(&MAGIC)?;
+        Ok(Self { writer })
+    }
+
+    pub fn write_puzzle(&mut self, board: &Board) -> io::Result<()> {
+        let bytes = board.to_bytes().map_err(invalid)?;
+        self.writer.write_all(&bytes)
+    }
+
+    pub fn write_solution(&mut self, board: &Board) -> io::Result<()> {
+        let bytes = board.to_solution_bytes().map_err(invalid)?;
+        self.writer.write_all(&bytes)
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads records written by `RecordWriter` one at a time.
+pub struct RecordReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> RecordReader<R> {
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid("Not a stream of sudoku records".to_string()));
+        }
+        Ok(Self { reader })
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = io::Result<Board>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        read_record(&mut self.reader).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc::Loc;
+
+    const PUZZLE: &str =
+        "...26.7.168..7..9.19...45..82.1...4...46.29...5...3.28..93...74.4..5..367.3.18...";
+    const SOLUTION: &str =
+        "435269781682571493197834562826195347374682915951743628519326874248957136763418259";
+
+    #[test]
+    fn test_board_to_bytes() {
+        let board = Board::from_line(PUZZLE).unwrap();
+        let bytes = board.to_bytes().unwrap();
+        assert_eq!(bytes[..3], [VERSION, Kind::Puzzle as u8, 3]);
+        assert_eq!(bytes.len(), 3 + 11 + 36 / 2);
+        assert_eq!(Board::from_bytes(&bytes), Ok(board));
+
+        let empty = Board::new(4);
+        assert_eq!(Board::from_bytes(&empty.to_bytes().unwrap()), Ok(empty));
+        let single = Board::from_line("1").unwrap();
+        assert_eq!(Board::from_bytes(&single.to_bytes().unwrap()), Ok(single));
+    }
+
+    #[test]
+    fn test_board_to_solution_bytes() {
+        let board = Board::from_line(SOLUTION).unwrap();
+        let bytes = board.to_solution_bytes().unwrap();
+        assert_eq!(bytes.len(), 5 + 11);
+        assert_eq!(Board::from_bytes(&bytes), Ok(board));
+
+        let board = Board::from_line("1234341221434321").unwrap();
+        assert_eq!(
+            Board::from_bytes(&board.to_solution_bytes().unwrap()),
+            Ok(board)
+        );
+
+        assert!(Board::from_line(PUZZLE)
+            .unwrap()
+            .to_solution_bytes()
+            .is_err());
+        let mut broken = Board::from_line(SOLUTION).unwrap();
+        broken.force_set(&Loc::new(1, 1), Some(6), Origin::Given);
+        assert!(broken.to_solution_bytes().is_err());
+    }
+
+    #[test]
+    fn test_board_from_bytes_errors() {
+        let bytes = Board::from_line(PUZZLE).unwrap().to_bytes().unwrap();
+        assert!(Board::from_bytes(&[]).is_err());
+        assert!(Board::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Board::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(Board::from_bytes(&[2, 0, 3]).is_err());
+        assert!(Board::from_bytes(&[VERSION, 7, 3]).is_err());
+        assert!(Board::from_bytes(&[VERSION, Kind::Puzzle as u8, 255]).is_err());
+        assert!(Board::from_bytes(&[VERSION, Kind::Solution as u8, MAX_SIZE + 1]).is_err());
+        assert!(Board::from_bytes(&[VERSION, Kind::Puzzle as u8, MAX_SIZE]).is_err());
+        assert!(Board::new(L::from(MAX_SIZE) + 1)
+            .to_solution_bytes()
+            .is_err());
+        assert!(Board::new(L::from(MAX_SIZE) + 1).to_bytes().is_err());
+        assert!(
+            Board::from_bytes(&[VERSION, Kind::Solution as u8, 2, 4, 0, 255, 255, 255, 255])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_records() {
+        let puzzle = Board::from_line(PUZZLE).unwrap();
+        let solution = Board::from_line(SOLUTION).unwrap();
+        let mut writer = RecordWriter::new(Vec::new()).unwrap();
+        for _ in 0..3 {
+            writer.write_puzzle(&puzzle).unwrap();
+            writer.write_solution(&solution).unwrap();
+        }
+        assert!(writer.write_solution(&puzzle).is_err());
+        assert!(writer
+            .write_puzzle(&Board::new(L::from(MAX_SIZE) + 1))
+            .is_err());
+        let bytes = writer.finish().unwrap();
+
+        let records: Vec<Board> = RecordReader::new(&bytes[..])
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(records.len(), 6);
+        assert_eq!(records[4], puzzle);
+        assert_eq!(records[5], solution);
+
+        assert!(RecordReader::new(&b"JSON"[..]).is_err());
+        let mut truncated = RecordReader::new(&bytes[..bytes.len() - 1]).unwrap();
+        assert!(truncated.any(|r| r.is_err()));
+    }
+}
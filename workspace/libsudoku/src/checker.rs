@@ -1,20 +1,23 @@
+use super::loc::L;
+use super::prelude::*;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct ValChecker {
     pub values: Vec<Vec<bool>>,
 }
 
 impl ValChecker {
-    pub fn new(top: u8) -> Self {
+    pub fn new(top: L) -> Self {
         Self {
             values: vec![vec![false; top.into()]; top.into()],
         }
     }
 
-    pub fn set(&mut self, loc: u8, value: u8) {
+    pub fn set(&mut self, loc: L, value: L) {
         self.values[usize::from(loc) - 1][usize::from(value) - 1] = true;
     }
 
-    pub fn unset(&mut self, loc: u8, value: u8) {
+    pub fn unset(&mut self, loc: L, value: L) {
         self.values[usize::from(loc) - 1][usize::from(value) - 1] = false;
     }
 
@@ -22,22 +25,23 @@ impl ValChecker {
         self.values.iter().flatten().all(|x| *x)
     }
 
-    pub fn available_values(&self, loc: u8) -> Vec<Option<u8>> {
+    pub fn available_values(&self, loc: L) -> Vec<Option<L>> {
         self.values[usize::from(loc) - 1]
             .iter()
             .enumerate()
-            .map(|(l, v)| if !*v { Some((l + 1) as u8) } else { None })
+            .map(|(l, v)| if !*v { Some((l + 1) as L) } else { None })
             .collect()
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
-    const TOP: u8 = 4;
+    const TOP: L = 4;
 
-    fn gen_values(top: u8) -> Vec<(u8, u8)> {
+    fn gen_values(top: L) -> Vec<(L, L)> {
         let top_range = 1..=top;
         top_range
             .clone()
@@ -98,8 +102,8 @@ mod tests {
         
 
         for value in 1..=TOP {
-            let before: Vec<Option<u8>> = (1..=TOP).map(|v| if v < value { None } else { Some(v) }).collect();
-            let after: Vec<Option<u8>> = (1..=TOP).map(|v| if v > value { Some(v) } else { None }).collect();
+            let before: Vec<Option<L>> = (1..=TOP).map(|v| if v < value { None } else { Some(v) }).collect();
+            let after: Vec<Option<L>> = (1..=TOP).map(|v| if v > value { Some(v) } else { None }).collect();
             for loc in 1..=TOP {
                 assert_eq!(temp.available_values(loc), before);
                 temp.set(loc, value);
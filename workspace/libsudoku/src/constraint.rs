@@ -0,0 +1,497 @@
+use alloc::sync::Arc;
+use core::any::Any;
+use core::fmt::Debug;
+
+use super::prelude::*;
+
+use super::Board;
+use super::loc::{Loc, L};
+
+/// Comparison of constraints behind trait objects, implemented for every
+/// `PartialEq` type.
+pub trait DynEq {
+    fn as_any(&self) -> &dyn Any;
+
+    /// Checks whether `other` is of the same type and equal to `self`.
+    fn dyn_eq(&self, other: &dyn Any) -> bool;
+}
+
+impl<T: Any + PartialEq> DynEq for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<T>() == Some(self)
+    }
+}
+
+/// Variant rule restricting which digits may be placed on a `Board`, on top of
+/// the classic row, column and box rules tracked by the `Verifier`.
+pub trait Constraint: Debug + Send + Sync + DynEq {
+    /// Checks whether `value` can be placed in `loc`, ignoring whatever `loc`
+    /// currently holds.
+    fn check(&self, board: &Board, loc: &Loc, value: L) -> bool;
+
+    /// Lists cells whose candidates may change when `loc` is set.
+    fn affected(&self, board: &Board, loc: &Loc) -> Vec<Loc>;
+
+    /// Lists digits ruled out for `loc` by this constraint.
+    fn eliminate(&self, board: &Board, loc: &Loc) -> Vec<L> {
+        (1..=board.top())
+            .filter(|value| !self.check(board, loc, *value))
+            .collect()
+    }
+
+    /// Checks whether a board satisfies the rule, requiring every cell to be filled.
+    fn is_satisfied(&self, board: &Board) -> bool {
+        board.iter().all(|(loc, value)| match value {
+            Some(v) => self.check(board, loc, *v),
+            None => false,
+        })
+    }
+}
+
+/// Set of constraints active on a board.
+///
+/// Constraints are immutable once added, so clones of a board share them.
+#[derive(Debug, Clone, Default)]
+pub struct Constraints(Vec<Arc<dyn Constraint>>);
+
+impl Constraints {
+    pub fn push(&mut self, constraint: Arc<dyn Constraint>) {
+        self.0.push(constraint);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn Constraint>> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl PartialEq for Constraints {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len()
+            && self
+                .0
+                .iter()
+                .zip(other.0.iter())
+                .all(|(l, r)| Arc::ptr_eq(l, r) || (**l).dyn_eq((**r).as_any()))
+    }
+}
+
+fn neighbours(top: L, loc: &Loc, deltas: &[(i32, i32)]) -> Vec<Loc> {
+    let top = i32::from(top);
+    deltas
+        .iter()
+        .map(|(dr, dc)| (i32::from(loc.row) + dr, i32::from(loc.col) + dc))
+        .filter(|(row, col)| (1..=top).contains(row) && (1..=top).contains(col))
+        .map(|(row, col)| Loc::new(row as L, col as L))
+        .collect()
+}
+
+const KNIGHT: [(i32, i32); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+const KING: [(i32, i32); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+const ORTHOGONAL: [(i32, i32); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Cells a chess knight's move apart cannot hold the same digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AntiKnight;
+
+impl Constraint for AntiKnight {
+    fn check(&self, board: &Board, loc: &Loc, value: L) -> bool {
+        self.affected(board, loc)
+            .iter()
+            .all(|other| board.value(other) != Some(value))
+    }
+
+    fn affected(&self, board: &Board, loc: &Loc) -> Vec<Loc> {
+        neighbours(board.top(), loc, &KNIGHT)
+    }
+}
+
+/// Cells a chess king's move apart cannot hold the same digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AntiKing;
+
+impl Constraint for AntiKing {
+    fn check(&self, board: &Board, loc: &Loc, value: L) -> bool {
+        self.affected(board, loc)
+            .iter()
+            .all(|other| board.value(other) != Some(value))
+    }
+
+    fn affected(&self, board: &Board, loc: &Loc) -> Vec<Loc> {
+        neighbours(board.top(), loc, &KING)
+    }
+}
+
+/// Orthogonally adjacent cells cannot hold consecutive digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonConsecutive;
+
+impl Constraint for NonConsecutive {
+    fn check(&self, board: &Board, loc: &Loc, value: L) -> bool {
+        self.affected(board, loc)
+            .iter()
+            .all(|other| board.value(other).is_none_or(|v| v.abs_diff(value) != 1))
+    }
+
+    fn affected(&self, board: &Board, loc: &Loc) -> Vec<Loc> {
+        neighbours(board.top(), loc, &ORTHOGONAL)
+    }
+}
+
+/// Digits strictly increase along the thermometer, starting from the bulb.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thermometer {
+    pub cells: Vec<Loc>,
+}
+
+impl Thermometer {
+    pub fn new(cells: Vec<Loc>) -> Self {
+        Self { cells }
+    }
+}
+
+impl Constraint for Thermometer {
+    fn check(&self, board: &Board, loc: &Loc, value: L) -> bool {
+        let index = match self.cells.iter().position(|l| l == loc) {
+            Some(i) => i,
+            None => return true,
+        };
+        let value = usize::from(value);
+        let after = self.cells.len() - 1 - index;
+        if value < index + 1 || value + after > usize::from(board.top()) {
+            return false;
+        }
+        self.cells
+            .iter()
+            .enumerate()
+            .all(|(i, other)| match board.value(other).map(usize::from) {
+                _ if i == index => true,
+                None => true,
+                Some(v) if i < index => v + (index - i) <= value,
+                Some(v) => v >= value + (i - index),
+            })
+    }
+
+    fn affected(&self, _board: &Board, loc: &Loc) -> Vec<Loc> {
+        if self.cells.contains(loc) {
+            self.cells.iter().filter(|l| *l != loc).copied().collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Digits along the arrow sum up to the digit in its circle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arrow {
+    pub circle: Loc,
+    pub cells: Vec<Loc>,
+}
+
+impl Arrow {
+    pub fn new(circle: Loc, cells: Vec<Loc>) -> Self {
+        Self { circle, cells }
+    }
+}
+
+impl Constraint for Arrow {
+    fn check(&self, board: &Board, loc: &Loc, value: L) -> bool {
+        if *loc != self.circle && !self.cells.contains(loc) {
+            return true;
+        }
+        let get = |l: &Loc| {
+            if l == loc {
+                Some(u32::from(value))
+            } else {
+                board.value(l).map(u32::from)
+            }
+        };
+        let (mut sum, mut empty) = (0, 0);
+        for l in self.cells.iter() {
+            match get(l) {
+                Some(v) => sum += v,
+                None => empty += 1,
+            }
+        }
+        match get(&self.circle) {
+            Some(circle) if empty == 0 => sum == circle,
+            Some(circle) => sum + empty <= circle,
+            None => sum + empty <= u32::from(board.top()),
+        }
+    }
+
+    fn affected(&self, _board: &Board, loc: &Loc) -> Vec<Loc> {
+        if *loc != self.circle && !self.cells.contains(loc) {
+            return Vec::new();
+        }
+        core::iter::once(&self.circle)
+            .chain(self.cells.iter())
+            .filter(|l| *l != loc)
+            .copied()
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dot {
+    /// Digits on both sides are consecutive.
+    White,
+    /// One digit is double the other.
+    Black,
+}
+
+/// Kropki dot placed between two adjacent cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Kropki {
+    pub first: Loc,
+    pub second: Loc,
+    pub dot: Dot,
+}
+
+impl Kropki {
+    pub fn new(first: Loc, second: Loc, dot: Dot) -> Self {
+        Self { first, second, dot }
+    }
+}
+
+impl Constraint for Kropki {
+    fn check(&self, board: &Board, loc: &Loc, value: L) -> bool {
+        let other = if *loc == self.first {
+            self.second
+        } else if *loc == self.second {
+            self.first
+        } else {
+            return true;
+        };
+        match (board.value(&other), self.dot) {
+            (None, Dot::White) => true,
+            (None, Dot::Black) => {
+                value.is_multiple_of(2) || u32::from(value) * 2 <= u32::from(board.top())
+            }
+            (Some(v), Dot::White) => v.abs_diff(value) == 1,
+            (Some(v), Dot::Black) => {
+                u32::from(v) == u32::from(value) * 2 || u32::from(value) == u32::from(v) * 2
+            }
+        }
+    }
+
+    fn affected(&self, _board: &Board, loc: &Loc) -> Vec<Loc> {
+        if *loc == self.first {
+            vec![self.second]
+        } else if *loc == self.second {
+            vec![self.first]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Marked cells can only hold even or only odd digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parity {
+    pub cells: Vec<Loc>,
+    pub even: bool,
+}
+
+impl Parity {
+    pub fn even(cells: Vec<Loc>) -> Self {
+        Self { cells, even: true }
+    }
+
+    pub fn odd(cells: Vec<Loc>) -> Self {
+        Self { cells, even: false }
+    }
+}
+
+impl Constraint for Parity {
+    fn check(&self, _board: &Board, loc: &Loc, value: L) -> bool {
+        !self.cells.contains(loc) || value.is_multiple_of(2) == self.even
+    }
+
+    fn affected(&self, _board: &Board, _loc: &Loc) -> Vec<Loc> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty() -> Board {
+        Board::new(3)
+    }
+
+    #[test]
+    fn test_neighbours() {
+        assert_eq!(
+            neighbours(9, &Loc::new(1, 1), &KNIGHT),
+            vec![Loc::new(2, 3), Loc::new(3, 2)]
+        );
+        assert_eq!(neighbours(9, &Loc::new(5, 5), &KING).len(), 8);
+        assert_eq!(
+            neighbours(9, &Loc::new(9, 9), &ORTHOGONAL),
+            vec![Loc::new(8, 9), Loc::new(9, 8)]
+        );
+    }
+
+    #[test]
+    fn test_anti_knight() {
+        let mut board = empty();
+        board.set(&Loc::new(1, 1), Some(5)).unwrap();
+        assert!(!AntiKnight.check(&board, &Loc::new(3, 2), 5));
+        assert!(AntiKnight.check(&board, &Loc::new(3, 2), 4));
+        assert!(AntiKnight.check(&board, &Loc::new(3, 3), 5));
+        assert_eq!(AntiKnight.eliminate(&board, &Loc::new(2, 3)), vec![5]);
+    }
+
+    #[test]
+    fn test_anti_king() {
+        let mut board = empty();
+        board.set(&Loc::new(2, 2), Some(7)).unwrap();
+        assert!(!AntiKing.check(&board, &Loc::new(3, 3), 7));
+        assert!(AntiKing.check(&board, &Loc::new(4, 4), 7));
+    }
+
+    #[test]
+    fn test_non_consecutive() {
+        let mut board = empty();
+        board.set(&Loc::new(5, 5), Some(4)).unwrap();
+        assert_eq!(
+            NonConsecutive.eliminate(&board, &Loc::new(5, 6)),
+            vec![3, 5]
+        );
+        assert!(NonConsecutive.check(&board, &Loc::new(6, 6), 5));
+    }
+
+    #[test]
+    fn test_thermometer() {
+        let thermo = Thermometer::new(vec![Loc::new(1, 1), Loc::new(1, 2), Loc::new(1, 3)]);
+        let mut board = empty();
+        assert_eq!(thermo.eliminate(&board, &Loc::new(1, 1)), vec![8, 9]);
+        assert_eq!(thermo.eliminate(&board, &Loc::new(1, 2)), vec![1, 9]);
+        board.set(&Loc::new(1, 2), Some(4)).unwrap();
+        assert_eq!(thermo.eliminate(&board, &Loc::new(1, 3)), vec![1, 2, 3, 4]);
+        assert!(thermo.check(&board, &Loc::new(1, 1), 3));
+        assert!(!thermo.check(&board, &Loc::new(1, 1), 4));
+        assert!(thermo.check(&board, &Loc::new(2, 2), 1));
+    }
+
+    #[test]
+    fn test_arrow() {
+        let arrow = Arrow::new(Loc::new(1, 1), vec![Loc::new(2, 2), Loc::new(3, 3)]);
+        let mut board = empty();
+        assert_eq!(arrow.eliminate(&board, &Loc::new(1, 1)), vec![1]);
+        assert_eq!(arrow.eliminate(&board, &Loc::new(2, 2)), vec![9]);
+        board.set(&Loc::new(1, 1), Some(5)).unwrap();
+        assert!(arrow.check(&board, &Loc::new(2, 2), 4));
+        assert!(!arrow.check(&board, &Loc::new(2, 2), 5));
+        board.set(&Loc::new(2, 2), Some(2)).unwrap();
+        assert_eq!(
+            arrow.eliminate(&board, &Loc::new(3, 3)),
+            vec![1, 2, 4, 5, 6, 7, 8, 9]
+        );
+    }
+
+    #[test]
+    fn test_kropki() {
+        let white = Kropki::new(Loc::new(1, 1), Loc::new(1, 2), Dot::White);
+        let black = Kropki::new(Loc::new(1, 1), Loc::new(1, 2), Dot::Black);
+        let mut board = empty();
+        assert_eq!(black.eliminate(&board, &Loc::new(1, 2)), vec![5, 7, 9]);
+        board.set(&Loc::new(1, 1), Some(4)).unwrap();
+        assert_eq!(
+            white.eliminate(&board, &Loc::new(1, 2)),
+            vec![1, 2, 4, 6, 7, 8, 9]
+        );
+        assert_eq!(
+            black.eliminate(&board, &Loc::new(1, 2)),
+            vec![1, 3, 4, 5, 6, 7, 9]
+        );
+    }
+
+    #[test]
+    fn test_parity() {
+        let even = Parity::even(vec![Loc::new(1, 1)]);
+        let odd = Parity::odd(vec![Loc::new(1, 1)]);
+        let board = empty();
+        assert_eq!(even.eliminate(&board, &Loc::new(1, 1)), vec![1, 3, 5, 7, 9]);
+        assert_eq!(odd.eliminate(&board, &Loc::new(1, 1)), vec![2, 4, 6, 8]);
+        assert!(even.check(&board, &Loc::new(1, 2), 1));
+    }
+
+    #[test]
+    fn test_constraints_eq() {
+        let mut left = Constraints::default();
+        let mut right = Constraints::default();
+        assert_eq!(left, right);
+
+        left.push(Arc::new(AntiKnight));
+        assert_ne!(left, right);
+
+        right.push(Arc::new(AntiKnight));
+        assert_eq!(left, right);
+
+        let (bulb, tip) = (Loc::new(1, 1), Loc::new(1, 2));
+        left.push(Arc::new(Thermometer::new(vec![bulb, tip])));
+        right.push(Arc::new(Thermometer::new(vec![tip, bulb])));
+        assert_ne!(left, right);
+    }
+
+    #[test]
+    fn test_constraints_eq_types() {
+        /// Rule printed like `AntiKnight` without being one.
+        mod other {
+            use super::*;
+
+            #[derive(Debug, PartialEq)]
+            pub struct AntiKnight;
+
+            impl Constraint for AntiKnight {
+                fn check(&self, _: &Board, _: &Loc, _: L) -> bool {
+                    true
+                }
+
+                fn affected(&self, _: &Board, _: &Loc) -> Vec<Loc> {
+                    Vec::new()
+                }
+            }
+        }
+
+        let (mut left, mut right) = (Constraints::default(), Constraints::default());
+        left.push(Arc::new(AntiKnight));
+        right.push(Arc::new(other::AntiKnight));
+        assert_eq!(format!("{:?}", left), format!("{:?}", right));
+        assert_ne!(left, right);
+    }
+}
@@ -0,0 +1,132 @@
+use super::loc::{Loc, L};
+use super::prelude::*;
+use super::symbols::SymbolSet;
+use super::{Board, Origin};
+
+/// Characters separating cells within a row of a drawing.
+const VERTICAL: [char; 3] = ['│', '║', '|'];
+
+/// Characters of the horizontal rules between rows of a drawing.
+const RULES: &str = "═─╔╗╚╝╠╣╦╩╬┼║│+-=|";
+
+/// Tokens standing for an empty cell, unless the alphabet uses them.
+const BLANKS: [&str; 3] = ["■", ".", "_"];
+
+impl Board {
+    /// Reads a grid rendered by `Board::draw`, or its plain ASCII variant
+    /// drawn with `+`, `-` and `|`.
+    ///
+    /// The alphabet is guessed from the cells: decimal numbers are tried
+    /// first, followed by the compact, hexadecimal and letter alphabets.
+    pub fn from_drawing(text: &str) -> Result<Self, String> {
+        let rows = Board::drawing_rows(text);
+        let top = L::try_from(rows.len()).map_err(|e| e.to_string())?;
+        let mut error = None;
+        for symbols in SymbolSet::presets(top) {
+            match Board::from_drawing_with(text, &symbols) {
+                Ok(board) => return Ok(board),
+                Err(e) => error = error.or(Some(e)),
+            }
+        }
+        Err(error.unwrap_or(format!("{} rows do not form a square board", rows.len())))
+    }
+
+    /// Reads a drawn grid with a given alphabet.
+    pub fn from_drawing_with(text: &str, symbols: &SymbolSet) -> Result<Self, String> {
+        let rows = Board::drawing_rows(text);
+        let size = Board::calc_size(rows.len())
+            .ok_or(format!("{} rows do not form a square board", rows.len()))?;
+        let mut board = Board::new(size);
+        board.set_symbols(symbols.clone())?;
+
+        for (row, cells) in (1..=board.top).zip(rows) {
+            if cells.len() != usize::from(board.top) {
+                return Err(format!("Row {} does not have {} cells", row, board.top));
+            }
+            for (col, cell) in (1..=board.top).zip(cells) {
+                let loc = Loc::new(row, col);
+                let value = match symbols.value(cell) {
+                    Some(v) => Some(v),
+                    None if BLANKS.contains(&cell) => None,
+                    None => return Err(format!("Unknown symbol '{}' in {}", cell, loc)),
+                };
+                board.force_set(&loc, value, Origin::Given);
+            }
+        }
+        Ok(board)
+    }
+
+    /// Splits a drawing into the cells of every row, skipping the rules.
+    fn drawing_rows(text: &str) -> Vec<Vec<&str>> {
+        text.lines()
+            .filter(|l| !l.chars().all(|c| c.is_whitespace() || RULES.contains(c)))
+            .map(|l| {
+                l.split(|c: char| c.is_whitespace() || VERTICAL.contains(&c))
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_from_drawing() {
+        let board = Board::from_line(
+            "...26.7.168..7..9.19...45..82.1...4...46.29...5...3.28..93...74.4..5..367.3.18...",
+        )
+        .unwrap();
+        assert_eq!(Board::from_drawing(&board.draw()), Ok(board.clone()));
+
+        let mut wide = Board::new(4);
+        wide.force_set(&Loc::new(1, 1), Some(16), Origin::Given);
+        wide.force_set(&Loc::new(16, 9), Some(10), Origin::Given);
+        assert_eq!(Board::from_drawing(&wide.draw()), Ok(wide));
+
+        let mut hex = board.clone();
+        hex.set_symbols(SymbolSet::hex(9).unwrap()).unwrap();
+        assert_eq!(Board::from_drawing(&hex.draw()), Ok(hex));
+    }
+
+    #[test]
+    fn test_board_from_drawing_ascii() {
+        let board = Board::from_drawing(
+            "
++-----+-----+
+| 1 . | 3 4 |
+| 3 4 | _ 2 |
++-----+-----+
+| 2|1 | 4|3 |
+| . 3 | 2 1 |
++-----+-----+
+",
+        )
+        .unwrap();
+        assert_eq!(board.to_line().unwrap(), "1.3434.22143.321");
+    }
+
+    #[test]
+    fn test_board_from_drawing_with() {
+        let symbols =
+            SymbolSet::custom(["★", "♥", "♦", "♣"].iter().map(|s| s.to_string()).collect())
+                .unwrap();
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        board.set_symbols(symbols.clone()).unwrap();
+        assert_eq!(
+            Board::from_drawing_with(&board.draw(), &symbols),
+            Ok(board.clone())
+        );
+        assert!(Board::from_drawing(&board.draw()).is_err());
+    }
+
+    #[test]
+    fn test_board_from_drawing_errors() {
+        assert!(Board::from_drawing("").is_err());
+        assert!(Board::from_drawing("| 1 2 |\n| 2 1 |").is_err());
+        assert!(Board::from_drawing("| 1 2 3 4 |\n| 1 |\n| 1 |\n| 1 |").is_err());
+        assert!(Board::from_drawing("| 1 2 3 4 |\n| 1 2 3 4 |\n| 1 2 3 4 |\n| 1 2 3 x |").is_err());
+    }
+}
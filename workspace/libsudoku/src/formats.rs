@@ -0,0 +1,717 @@
+use core::str::FromStr;
+
+#[cfg(feature = "json")]
+use serde_json::Value;
+
+use super::loc::{Loc, L};
+use super::prelude::*;
+use super::symbols::SymbolSet;
+use super::{Board, Origin};
+
+/// Metadata codes used in SadMan headers, e.g. `#A author`.
+const SADMAN_CODES: [(char, &str); 7] = [
+    ('A', "author"),
+    ('D', "description"),
+    ('C', "comment"),
+    ('B', "date"),
+    ('S', "source"),
+    ('L', "level"),
+    ('U', "url"),
+];
+
+/// File formats a `Puzzle` can be read from and written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Nested JSON arrays, as produced by `Serialize for Board`.
+    #[cfg(feature = "json")]
+    Json,
+    /// Single line of cells, see `Board::from_line`.
+    Line,
+    /// SadMan Software `.sdk`, with `#` metadata and `[Puzzle]`/`[State]` sections.
+    SadMan,
+    /// SadMan Software `.sdx`, one row per line with givens, `u`-prefixed
+    /// player digits and candidate lists separated by spaces. Empty cells with
+    /// fewer than two pencil marks are written as `.`.
+    SadManExtended,
+    /// Simple Sudoku `.ss`, a grid with `|`, `-` and `*` borders.
+    SimpleSudoku,
+    /// Pencil-mark grid with candidates of every cell, see `Board::from_pencil_marks`.
+    PencilMarks,
+    /// SudoCue `.sdk`, free-form `#` comments followed by a plain grid.
+    SudoCue,
+    /// Box-drawn grid, as printed by `Board::draw`.
+    Drawing,
+}
+
+impl Format {
+    pub const ALL: [Format; 7 + cfg!(feature = "json") as usize] = [
+        #[cfg(feature = "json")]
+        Format::Json,
+        Format::Line,
+        Format::SadMan,
+        Format::SadManExtended,
+        Format::SimpleSudoku,
+        Format::PencilMarks,
+        Format::SudoCue,
+        Format::Drawing,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "json")]
+            Format::Json => "json",
+            Format::Line => "line",
+            Format::SadMan => "sdk",
+            Format::SadManExtended => "sdx",
+            Format::SimpleSudoku => "ss",
+            Format::PencilMarks => "pm",
+            Format::SudoCue => "sudocue",
+            Format::Drawing => "draw",
+        }
+    }
+
+    /// Guesses the format of a puzzle by its content.
+    pub fn detect(text: &str) -> Option<Format> {
+        let text = text.trim();
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+        let grid: Vec<&str> = lines
+            .iter()
+            .filter(|l| !l.starts_with('#'))
+            .copied()
+            .collect();
+
+        if text.is_empty() {
+            return None;
+        }
+        #[cfg(feature = "json")]
+        if text.starts_with('[') && !text.starts_with("[Puzzle]") {
+            return Some(Format::Json);
+        }
+        if text.starts_with('╔') {
+            Some(Format::Drawing)
+        } else if lines.iter().any(|l| *l == "[Puzzle]" || *l == "[State]") {
+            Some(Format::SadMan)
+        } else if grid.iter().any(|l| {
+            let tokens: Vec<&str> = l.split_whitespace().collect();
+            tokens.len() > 1
+                && tokens
+                    .iter()
+                    .all(|t| *t == "." || t.chars().all(|c| c.is_alphanumeric()))
+        }) {
+            Some(Format::SadManExtended)
+        } else if grid.iter().any(|l| l.starts_with('|') && l.contains(' ')) {
+            Some(Format::PencilMarks)
+        } else if grid
+            .iter()
+            .any(|l| l.starts_with('*') || l.starts_with('|'))
+        {
+            Some(Format::SimpleSudoku)
+        } else if lines.len() > grid.len() {
+            Some(Format::SudoCue)
+        } else if grid.len() == 1 {
+            Some(Format::Line)
+        } else {
+            Some(Format::SimpleSudoku)
+        }
+    }
+
+    /// Picks the format matching a file extension. SadMan and SudoCue both use
+    /// `.sdk`, so it gives `None`, like unknown extensions.
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        match extension.to_ascii_lowercase().as_str() {
+            #[cfg(feature = "json")]
+            "json" => Some(Format::Json),
+            "txt" | "line" => Some(Format::Line),
+            "sdx" => Some(Format::SadManExtended),
+            "ss" => Some(Format::SimpleSudoku),
+            _ => None,
+        }
+    }
+
+    /// Picks the format of a file from its extension, falling back to
+    /// `Format::detect` on its content.
+    pub fn from_file(extension: &str, text: &str) -> Option<Format> {
+        Format::from_extension(extension).or_else(|| Format::detect(text))
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Format::ALL
+            .into_iter()
+            .find(|f| f.name() == s)
+            .ok_or(format!("Unknown format '{}'", s))
+    }
+}
+
+/// Lists formats by name in `--help` and shell completions.
+#[cfg(feature = "cli")]
+impl clap::ValueEnum for Format {
+    fn value_variants<'a>() -> &'a [Self] {
+        &Format::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        Some(clap::builder::PossibleValue::new(self.name()))
+    }
+}
+
+/// Puzzle read from a file, along with its metadata, the player's progress
+/// and pencil marks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Puzzle {
+    /// Givens of the puzzle.
+    pub board: Board,
+    /// Current position including digits placed by the player and pencil
+    /// marks, if saved.
+    pub state: Option<Board>,
+    /// Header entries, such as `author` or `level`, in file order.
+    pub metadata: Vec<(String, String)>,
+}
+
+impl From<Board> for Puzzle {
+    fn from(board: Board) -> Self {
+        Self {
+            board,
+            state: None,
+            metadata: Vec::new(),
+        }
+    }
+}
+
+impl Puzzle {
+    /// Reads a puzzle, detecting its format by content.
+    pub fn read(text: &str) -> Result<Self, String> {
+        let format = Format::detect(text).ok_or("Puzzle is empty")?;
+        Puzzle::read_as(text, format)
+    }
+
+    pub fn read_as(text: &str, format: Format) -> Result<Self, String> {
+        match format {
+            #[cfg(feature = "json")]
+            Format::Json => {
+                let value = serde_json::from_str::<Value>(text).map_err(|e| e.to_string())?;
+                Ok(Board::from_json(&value)?.into())
+            }
+            Format::Line => Ok(Board::from_line(text)?.into()),
+            Format::SadMan => read_sadman(text),
+            Format::SadManExtended => read_sdx(text),
+            Format::SimpleSudoku => Ok(Board::from_line(&strip_simple(text))?.into()),
+            Format::PencilMarks => Ok(Board::from_pencil_marks(text)?.into()),
+            Format::SudoCue => read_sudocue(text),
+            Format::Drawing => Ok(Board::from_drawing(text)?.into()),
+        }
+    }
+
+    /// Writes the puzzle in `format`, failing when the format cannot represent
+    /// the board, like single character formats for boards above 61x61.
+    pub fn write(&self, format: Format) -> Result<String, String> {
+        Ok(match format {
+            #[cfg(feature = "json")]
+            Format::Json => format!("{}\n", Value::from(&self.board)),
+            Format::Line => format!("{}\n", self.board.to_line()?),
+            Format::SadMan => self.write_sadman()?,
+            Format::SadManExtended => self.write_sdx()?,
+            Format::SimpleSudoku => write_simple(&self.board)?,
+            Format::PencilMarks => self.state.as_ref().unwrap_or(&self.board).to_pencil_marks()?,
+            Format::SudoCue => self.write_sudocue()?,
+            Format::Drawing => self.board.draw(),
+        })
+    }
+
+    /// Finds the first metadata entry with the given key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn write_sadman(&self) -> Result<String, String> {
+        let mut output = String::from("[Puzzle]\n");
+        for (key, value) in self.metadata.iter() {
+            match SADMAN_CODES.iter().find(|(_, k)| k == key) {
+                Some((code, _)) => output.push_str(&format!("#{} {}\n", code, value)),
+                None if key.is_empty() || key.contains(|c: char| !is_key_char(c)) => {
+                    return Err(format!("Key '{}' cannot be written to SadMan", key))
+                }
+                None => output.push_str(&format!("#C {}: {}\n", key, value)),
+            }
+        }
+        output.push_str(&write_rows(&self.board)?);
+        if let Some(state) = self.state.as_ref() {
+            output.push_str("[State]\n");
+            output.push_str(&write_rows(state)?);
+        }
+        Ok(output)
+    }
+
+    fn write_sdx(&self) -> Result<String, String> {
+        let current = self.state.as_ref().unwrap_or(&self.board);
+        let symbols = SymbolSet::compact(current.top())?;
+        let symbol = |v: L| symbols.symbol(v).unwrap_or("?");
+        let mut output = String::new();
+        for row in 1..=current.top() {
+            let cells: Vec<String> = (1..=current.top())
+                .map(|col| {
+                    let loc = Loc::new(row, col);
+                    match (self.board.value(&loc), current.value(&loc)) {
+                        (Some(v), _) => symbol(v).to_string(),
+                        (None, Some(v)) => format!("u{}", symbol(v)),
+                        (None, None) => match current.marks(&loc) {
+                            Some(marks) if marks.len() > 1 => {
+                                marks.iter().map(|v| symbol(*v)).collect()
+                            }
+                            _ => ".".to_string(),
+                        },
+                    }
+                })
+                .collect();
+            output.push_str(&cells.join(" "));
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    fn write_sudocue(&self) -> Result<String, String> {
+        let mut output = String::new();
+        for (key, value) in self.metadata.iter() {
+            output.push_str(&format!("# {}: {}\n", key, value));
+        }
+        output.push_str(&write_rows(&self.board)?);
+        Ok(output)
+    }
+}
+
+fn write_rows(board: &Board) -> Result<String, String> {
+    let line = board.to_line()?;
+    let top = usize::from(board.top());
+    let chars: Vec<char> = line.chars().collect();
+    Ok(chars
+        .chunks(top)
+        .map(|row| format!("{}\n", row.iter().collect::<String>()))
+        .collect())
+}
+
+fn read_metadata(line: &str) -> Option<(String, String)> {
+    let line = line.strip_prefix('#')?;
+    let mut chars = line.chars();
+    let code = chars.next()?;
+    let rest = chars.as_str();
+    if let Some((_, key)) = SADMAN_CODES.iter().find(|(c, _)| *c == code) {
+        if rest.is_empty() || rest.starts_with(' ') {
+            // Keys without a code of their own are written as `#C key: value`.
+            return match read_entry(rest) {
+                Some((k, v)) if code == 'C' && SADMAN_CODES.iter().all(|(_, n)| *n != k) => {
+                    Some((k, v))
+                }
+                _ => Some((key.to_string(), rest.trim().to_string())),
+            };
+        }
+    }
+    let line = line.trim();
+    read_entry(line).or_else(|| Some(("comment".to_string(), line.to_string())))
+}
+
+/// Whether `c` may appear in a key read by `read_entry`.
+fn is_key_char(c: char) -> bool {
+    !c.is_whitespace() && !c.is_uppercase() && c != ':'
+}
+
+/// Parses `key: value`, with a lowercase key without spaces.
+fn read_entry(line: &str) -> Option<(String, String)> {
+    match line.trim().split_once(':') {
+        Some((key, value)) if !key.trim().is_empty() && !key.contains(' ') => {
+            Some((key.trim().to_lowercase(), value.trim().to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn read_sadman(text: &str) -> Result<Puzzle, String> {
+    let mut metadata = Vec::new();
+    let (mut puzzle, mut state) = (String::new(), String::new());
+    let mut section = &mut puzzle;
+    for line in text.lines().map(str::trim) {
+        match line {
+            "[Puzzle]" => section = &mut puzzle,
+            "[State]" => section = &mut state,
+            l if l.starts_with('#') => metadata.extend(read_metadata(l)),
+            l => section.push_str(l),
+        }
+    }
+    let board = Board::from_line(&puzzle)?;
+    let state = if state.trim().is_empty() {
+        None
+    } else {
+        let mut state = Board::from_line(&state)?;
+        if state.top() != board.top() {
+            return Err("State does not match the puzzle size".to_string());
+        }
+        for loc in board.keys() {
+            if let (None, Some(v)) = (board.value(loc), state.value(loc)) {
+                state.force_set(loc, Some(v), Origin::Entered);
+            }
+        }
+        Some(state)
+    };
+    Ok(Puzzle {
+        board,
+        state,
+        metadata,
+    })
+}
+
+fn read_sdx(text: &str) -> Result<Puzzle, String> {
+    let mut metadata = Vec::new();
+    let mut rows = Vec::new();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with('#') {
+            metadata.extend(read_metadata(line));
+        } else {
+            rows.push(line.split_whitespace().collect::<Vec<&str>>());
+        }
+    }
+    let size = Board::calc_size(rows.len())
+        .ok_or(format!("{} rows do not form a square board", rows.len()))?;
+    let mut board = Board::new(size);
+    let mut state = Board::new(size);
+    let symbols = SymbolSet::compact(board.top())?;
+    let value = |loc: &Loc, c: char| {
+        symbols
+            .value(c.encode_utf8(&mut [0; 4]))
+            .ok_or(format!("Unknown symbol '{}' in {}", c, loc))
+    };
+
+    for (row, cells) in (1..=board.top()).zip(rows) {
+        if cells.len() != usize::from(board.top()) {
+            return Err(format!("Row {} does not have {} cells", row, board.top()));
+        }
+        for (col, cell) in (1..=board.top()).zip(cells) {
+            let loc = Loc::new(row, col);
+            let chars: Vec<char> = cell.chars().collect();
+            match chars[..] {
+                ['u', c] => state.force_set(&loc, Some(value(&loc, c)?), Origin::Entered),
+                ['.'] | ['0'] => (),
+                [c] => {
+                    let v = value(&loc, c)?;
+                    board.force_set(&loc, Some(v), Origin::Given);
+                    state.force_set(&loc, Some(v), Origin::Given);
+                }
+                _ => {
+                    let marks = chars
+                        .iter()
+                        .map(|c| value(&loc, *c))
+                        .collect::<Result<Vec<L>, String>>()?;
+                    state.set_marks(&loc, Some(marks));
+                }
+            }
+        }
+    }
+
+    let state = if state == board { None } else { Some(state) };
+    Ok(Puzzle {
+        board,
+        state,
+        metadata,
+    })
+}
+
+fn strip_simple(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.starts_with('*') && !l.starts_with('#'))
+        .map(|l| l.replace(['X', 'x'], "."))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn write_simple(board: &Board) -> Result<String, String> {
+    let size = usize::from(board.size());
+    let line = board.to_line()?;
+    let chars: Vec<char> = line.chars().collect();
+    let border = format!("*{}*\n", "-".repeat(size * size + size - 1));
+    let separator = format!("|{}|\n", vec!["-".repeat(size); size].join("+"));
+    let mut output = border.clone();
+    for (r, row) in chars.chunks(size * size).enumerate() {
+        if r > 0 && r % size == 0 {
+            output.push_str(&separator);
+        }
+        let boxes: Vec<String> = row.chunks(size).map(|b| b.iter().collect()).collect();
+        output.push_str(&format!("|{}|\n", boxes.join("|")));
+    }
+    output.push_str(&border);
+    Ok(output)
+}
+
+fn read_sudocue(text: &str) -> Result<Puzzle, String> {
+    let mut metadata = Vec::new();
+    let mut grid = String::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('#') {
+            metadata.extend(read_metadata(line));
+        } else {
+            grid.push_str(line);
+            grid.push('\n');
+        }
+    }
+    let mut puzzle = Puzzle::from(Board::from_line(&grid)?);
+    puzzle.metadata = metadata;
+    Ok(puzzle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "...26.7.168..7..9.19...45..82.1...4...46.29...5...3.28..93...74.4..5..367.3.18...";
+
+    fn puzzle() -> Board {
+        Board::from_line(PUZZLE).unwrap()
+    }
+
+    #[test]
+    fn test_format_names() {
+        for format in Format::ALL {
+            assert_eq!(format.name().parse(), Ok(format));
+        }
+        assert!("pdf".parse::<Format>().is_err());
+        assert_eq!(Format::from_extension("SDX"), Some(Format::SadManExtended));
+        assert_eq!(Format::from_extension("sdk"), None);
+        assert_eq!(Format::from_extension("ss"), Some(Format::SimpleSudoku));
+        assert_eq!(Format::from_extension("pdf"), None);
+    }
+
+    #[test]
+    fn test_format_detect() {
+        let mut puzzle = Puzzle::from(puzzle());
+        puzzle
+            .metadata
+            .push(("author".to_string(), "Jane Doe".to_string()));
+        for format in Format::ALL {
+            assert_eq!(
+                Format::detect(&puzzle.write(format).unwrap()),
+                Some(format),
+                "{:?}",
+                format
+            );
+        }
+        assert_eq!(Format::detect(" \n "), None);
+
+        let sadman = puzzle.write(Format::SadMan).unwrap();
+        let sudocue = puzzle.write(Format::SudoCue).unwrap();
+        assert_eq!(Format::from_file("sdk", &sadman), Some(Format::SadMan));
+        assert_eq!(Format::from_file("sdk", &sudocue), Some(Format::SudoCue));
+        let simple = Some(Format::SimpleSudoku);
+        assert_eq!(Format::from_file("ss", &sudocue), simple);
+        assert_eq!(
+            Format::detect(&write_rows(&puzzle.board).unwrap()),
+            Some(Format::SimpleSudoku)
+        );
+    }
+
+    #[test]
+    fn test_puzzle_round_trip() {
+        let mut puzzle = Puzzle::from(puzzle());
+        // Pencil marks write every candidate, so they are checked separately.
+        for format in Format::ALL
+            .into_iter()
+            .filter(|f| *f != Format::PencilMarks)
+        {
+            let text = puzzle.write(format).unwrap();
+            assert_eq!(Puzzle::read(&text), Ok(puzzle.clone()), "{}", text);
+        }
+
+        puzzle.metadata = vec![
+            ("author".to_string(), "Jane Doe".to_string()),
+            ("level".to_string(), "Hard".to_string()),
+        ];
+        for format in [Format::SadMan, Format::SudoCue] {
+            let text = puzzle.write(format).unwrap();
+            assert_eq!(Puzzle::read(&text), Ok(puzzle.clone()), "{}", text);
+        }
+
+        // Keys without a SadMan code go to comments and are read back from them.
+        puzzle
+            .metadata
+            .push(("rating".to_string(), "1.2: medium".to_string()));
+        let text = puzzle.write(Format::SadMan).unwrap();
+        assert!(text.contains("#C rating: 1.2: medium\n"), "{}", text);
+        assert_eq!(Puzzle::read(&text), Ok(puzzle.clone()), "{}", text);
+        puzzle
+            .metadata
+            .push(("Puzzle number".to_string(), "12".to_string()));
+        assert!(puzzle.write(Format::SadMan).is_err());
+    }
+
+    #[test]
+    fn test_read_sadman() {
+        let text = "[Puzzle]
+#A Jane Doe
+#D Daily puzzle
+#L Easy
+#Cnot a code
+...26.7.1
+68..7..9.
+19...45..
+82.1...4.
+..46.29..
+.5...3.28
+..93...74
+.4..5..36
+7.3.18...
+[State]
+4..26.7.1
+68..7..9.
+19...45..
+82.1...4.
+..46.29..
+.5...3.28
+..93...74
+.4..5..36
+7.3.18...
+";
+        let read = Puzzle::read(text).unwrap();
+        assert_eq!(read.board, puzzle());
+        assert_eq!(read.get("author"), Some("Jane Doe"));
+        assert_eq!(read.get("description"), Some("Daily puzzle"));
+        assert_eq!(read.get("level"), Some("Easy"));
+        assert_eq!(read.get("comment"), Some("Cnot a code"));
+        let state = read.state.as_ref().unwrap();
+        assert_eq!(state.value(&Loc::new(1, 1)), Some(4));
+        assert_eq!(state.origin(&Loc::new(1, 1)), Some(Origin::Entered));
+        assert_eq!(state.origin(&Loc::new(1, 4)), Some(Origin::Given));
+        assert_eq!(Puzzle::read(&read.write(Format::SadMan).unwrap()), Ok(read));
+    }
+
+    #[test]
+    fn test_read_sdx() {
+        let text = "
+u2 13 3 4
+3 4 u1 2
+14 1 4 3
+4 3 2 1
+";
+        let read = Puzzle::read(text).unwrap();
+        assert_eq!(read.board.value(&Loc::new(1, 1)), None);
+        assert_eq!(read.board.value(&Loc::new(1, 3)), Some(3));
+        assert_eq!(read.board.value(&Loc::new(2, 3)), None);
+        let state = read.state.as_ref().unwrap();
+        assert_eq!(state.value(&Loc::new(1, 1)), Some(2));
+        assert_eq!(state.value(&Loc::new(2, 3)), Some(1));
+        assert_eq!(state.origin(&Loc::new(2, 3)), Some(Origin::Entered));
+        assert_eq!(state.origin(&Loc::new(1, 3)), Some(Origin::Given));
+        assert_eq!(state.marks(&Loc::new(1, 2)), Some(&vec![1, 3]));
+        assert_eq!(state.marks(&Loc::new(3, 1)), Some(&vec![1, 4]));
+        assert_eq!(read.write(Format::SadManExtended).unwrap(), text.trim_start());
+
+        let mut read = read;
+        read.state
+            .as_mut()
+            .unwrap()
+            .set_marks(&Loc::new(1, 2), Some(vec![1]));
+        assert_eq!(
+            read.write(Format::SadManExtended).unwrap(),
+            "u2 . 3 4\n3 4 u1 2\n14 1 4 3\n4 3 2 1\n"
+        );
+        assert!(Puzzle::from(Board::new(8))
+            .write(Format::SadManExtended)
+            .is_err());
+
+        let read = Puzzle::read("1 2 3 4\n3 4 1 2\n2 1 4 3\n4 3 2 1").unwrap();
+        assert_eq!(read.state, None);
+        assert!(read.board.is_solved());
+
+        assert!(Puzzle::read_as("1 2\n3", Format::SadManExtended).is_err());
+        assert!(Puzzle::read_as(
+            "u9 12 3 4\n1 2 3 4\n1 2 3 4\n1 2 3 4",
+            Format::SadManExtended
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_read_simple_sudoku() {
+        let text = "*-----------*
+|...|26.|7.1|
+|68.|.7.|.9.|
+|19.|..4|5..|
+|---+---+---|
+|82.|1..|.4.|
+|..4|6.2|9..|
+|.5.|..3|.28|
+|---+---+---|
+|..9|3..|.74|
+|.4.|.5.|.36|
+|7.3|.18|...|
+*-----------*
+";
+        let read = Puzzle::read(text).unwrap();
+        assert_eq!(read.board, puzzle());
+        assert_eq!(read.write(Format::SimpleSudoku).unwrap(), text);
+
+        let plain = "X2X4\n3412\n2143\n4321\n";
+        let read = Puzzle::read_as(plain, Format::SimpleSudoku).unwrap();
+        assert_eq!(read.board.to_line().unwrap(), ".2.4341221434321");
+    }
+
+    #[test]
+    fn test_read_pencil_marks() {
+        let text = "
+*--------------*
+| 13 2  | 13 4 |
+| 3  4  | 1  2 |
+|-------+------|
+| 2  13 | 4  3 |
+| 4  3  | 2  1 |
+*--------------*
+";
+        let read = Puzzle::read(text).unwrap();
+        assert_eq!(read.board.to_line().unwrap(), ".2.434122.434321");
+        assert_eq!(read.board.marks(&Loc::new(1, 1)), Some(&vec![1, 3]));
+        assert_eq!(read.board.candidates(&Loc::new(1, 1)), vec![1]);
+        assert_eq!(
+            read.write(Format::PencilMarks).unwrap(),
+            Board::from_line(".2.434122.434321")
+                .unwrap()
+                .to_pencil_marks()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_sudocue() {
+        let text = "# Generated by SudoCue
+# Rating: 1.2
+..34
+3412
+2143
+4321
+";
+        let read = Puzzle::read(text).unwrap();
+        assert_eq!(read.board.to_line().unwrap(), "..34341221434321");
+        assert_eq!(
+            read.metadata,
+            vec![
+                ("comment".to_string(), "Generated by SudoCue".to_string()),
+                ("rating".to_string(), "1.2".to_string()),
+            ]
+        );
+        assert_eq!(Puzzle::read(&read.write(Format::SudoCue).unwrap()), Ok(read));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(Puzzle::read("").is_err());
+        assert!(Puzzle::read("[[1, 2]]").is_err());
+        assert!(Puzzle::read("[Puzzle]\n12\n").is_err());
+        assert!(Puzzle::read("[Puzzle]\n1...\n....\n....\n....\n[State]\n1\n").is_err());
+    }
+}
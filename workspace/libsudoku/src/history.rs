@@ -0,0 +1,381 @@
+use super::loc::{Loc, L};
+use super::prelude::*;
+#[cfg(feature = "serde")]
+use super::state::GameState;
+use super::{Board, Origin};
+
+/// Single change of a board recorded by `History`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "lowercase"))]
+pub enum Edit {
+    /// Placement or removal of a digit.
+    Value {
+        loc: Loc,
+        before: Option<(L, Origin)>,
+        after: Option<(L, Origin)>,
+    },
+    /// Change of the pencil marks of a cell.
+    Marks {
+        loc: Loc,
+        before: Option<Vec<L>>,
+        after: Option<Vec<L>>,
+    },
+}
+
+impl Edit {
+    pub fn loc(&self) -> &Loc {
+        match self {
+            Edit::Value { loc, .. } | Edit::Marks { loc, .. } => loc,
+        }
+    }
+
+    /// Fails for edits of cells outside of `board` or of digits out of range.
+    fn check(&self, board: &Board) -> Result<(), String> {
+        let loc = self.loc();
+        if !board.contains_key(loc) {
+            return Err(format!("{} is outside of the board", loc));
+        }
+        if let Edit::Value { before, after, .. } = self {
+            let mut digits = before.iter().chain(after.iter()).map(|(v, _)| *v);
+            if let Some(v) = digits.find(|v| !(1..=board.top()).contains(v)) {
+                return Err(format!("'{}' in {} is not a digit of the board", v, loc));
+            }
+        }
+        Ok(())
+    }
+
+    fn inverse(&self) -> Self {
+        match self.clone() {
+            Edit::Value { loc, before, after } => Edit::Value {
+                loc,
+                before: after,
+                after: before,
+            },
+            Edit::Marks { loc, before, after } => Edit::Marks {
+                loc,
+                before: after,
+                after: before,
+            },
+        }
+    }
+
+    fn apply(&self, board: &mut Board) {
+        match self {
+            Edit::Value { loc, after, .. } => match after {
+                Some((value, origin)) => board.force_set(loc, Some(*value), *origin),
+                None => board.force_set(loc, None, Origin::Entered),
+            },
+            Edit::Marks { loc, after, .. } => board.set_marks(loc, after.clone()),
+        }
+    }
+}
+
+/// Board with a journal of its changes, supporting unlimited undo and redo
+/// and named checkpoints.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "HistoryData", into = "HistoryData"))]
+pub struct History {
+    board: Board,
+    edits: Vec<Edit>,
+    position: usize,
+    checkpoints: Vec<(String, usize)>,
+}
+
+impl History {
+    pub fn new(board: Board) -> Self {
+        Self {
+            board,
+            edits: Vec::new(),
+            position: 0,
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// Applies `edits` to `board` in order, e.g. to replay a recorded search.
+    pub fn replay(board: Board, edits: &[Edit]) -> Result<Self, String> {
+        let mut history = History::new(board);
+        for edit in edits {
+            edit.check(&history.board)?;
+            history.record(edit.clone());
+        }
+        Ok(history)
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// Edits applied so far, without the ones undone.
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits[..self.position]
+    }
+
+    fn value_edit(&self, loc: &Loc, after: Option<(L, Origin)>) -> Edit {
+        let before = self.board.value(loc).zip(self.board.origin(loc));
+        Edit::Value {
+            loc: *loc,
+            before,
+            after,
+        }
+    }
+
+    fn record(&mut self, edit: Edit) {
+        edit.apply(&mut self.board);
+        self.edits.truncate(self.position);
+        self.checkpoints.retain(|(_, p)| *p <= self.position);
+        self.edits.push(edit);
+        self.position += 1;
+    }
+
+    /// Records `Board::set`, failing without a trace on givens.
+    pub fn set(&mut self, loc: &Loc, value: Option<L>) -> Result<(), String> {
+        let edit = self.value_edit(loc, value.map(|v| (v, Origin::Entered)));
+        self.board.set(loc, value)?;
+        self.record(edit);
+        Ok(())
+    }
+
+    /// Records `Board::try_set`, failing without a trace on broken rules.
+    pub fn try_set(&mut self, loc: &Loc, value: Option<L>) -> Result<(), String> {
+        let edit = self.value_edit(loc, value.map(|v| (v, Origin::Entered)));
+        self.board.try_set(loc, value)?;
+        self.record(edit);
+        Ok(())
+    }
+
+    /// Records `Board::force_set`.
+    pub fn force_set(&mut self, loc: &Loc, value: Option<L>, origin: Origin) {
+        let edit = self.value_edit(loc, value.map(|v| (v, origin)));
+        self.record(edit);
+    }
+
+    /// Records `Board::set_marks`.
+    pub fn set_marks(&mut self, loc: &Loc, marks: Option<Vec<L>>) {
+        let mut after = marks;
+        if let Some(marks) = after.as_mut() {
+            marks.retain(|v| (1..=self.board.top()).contains(v));
+            marks.sort();
+            marks.dedup();
+        }
+        let edit = Edit::Marks {
+            loc: *loc,
+            before: self.board.marks(loc).cloned(),
+            after,
+        };
+        self.record(edit);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.position < self.edits.len()
+    }
+
+    /// Reverts the last edit, returning it.
+    pub fn undo(&mut self) -> Option<&Edit> {
+        if !self.can_undo() {
+            return None;
+        }
+        self.position -= 1;
+        let edit = &self.edits[self.position];
+        edit.inverse().apply(&mut self.board);
+        Some(edit)
+    }
+
+    /// Applies again the last edit undone, returning it.
+    pub fn redo(&mut self) -> Option<&Edit> {
+        if !self.can_redo() {
+            return None;
+        }
+        let edit = &self.edits[self.position];
+        edit.apply(&mut self.board);
+        self.position += 1;
+        Some(edit)
+    }
+
+    /// Remembers the current position under `name`, replacing an older
+    /// checkpoint with the same name.
+    pub fn checkpoint(&mut self, name: &str) {
+        self.checkpoints.retain(|(n, _)| n != name);
+        self.checkpoints.push((name.to_string(), self.position));
+    }
+
+    pub fn checkpoints(&self) -> Vec<&str> {
+        self.checkpoints.iter().map(|(n, _)| n.as_str()).collect()
+    }
+
+    /// Undoes or redoes edits until the board is back at checkpoint `name`.
+    pub fn restore(&mut self, name: &str) -> Result<(), String> {
+        let target = self
+            .checkpoints
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, p)| *p)
+            .ok_or(format!("Unknown checkpoint '{}'", name))?;
+        while self.position > target {
+            self.undo();
+        }
+        while self.position < target {
+            self.redo();
+        }
+        Ok(())
+    }
+}
+
+/// Serialized form of `History`, storing the board with its origins.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HistoryData {
+    board: GameState,
+    edits: Vec<Edit>,
+    position: usize,
+    checkpoints: Vec<(String, usize)>,
+}
+
+#[cfg(feature = "serde")]
+impl From<History> for HistoryData {
+    fn from(other: History) -> Self {
+        Self {
+            board: GameState::from(&other.board),
+            edits: other.edits,
+            position: other.position,
+            checkpoints: other.checkpoints,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<HistoryData> for History {
+    type Error = String;
+
+    fn try_from(other: HistoryData) -> Result<Self, Self::Error> {
+        if other.position > other.edits.len()
+            || other
+                .checkpoints
+                .iter()
+                .any(|(_, p)| *p > other.edits.len())
+        {
+            return Err("History position is out of range".to_string());
+        }
+        let board = Board::try_from(other.board)?;
+        for edit in other.edits.iter() {
+            edit.check(&board)?;
+        }
+        Ok(Self {
+            board,
+            edits: other.edits,
+            position: other.position,
+            checkpoints: other.checkpoints,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> History {
+        History::new(Board::from_line("1.3434.22143.321").unwrap())
+    }
+
+    #[test]
+    fn test_history_undo_redo() {
+        let mut history = history();
+        let start = history.board().clone();
+        assert!(history.undo().is_none());
+
+        history.set(&Loc::new(2, 3), Some(1)).unwrap();
+        history.set(&Loc::new(1, 2), Some(2)).unwrap();
+        history.set_marks(&Loc::new(4, 1), Some(vec![4, 1, 9]));
+        assert!(history.set(&Loc::new(1, 1), None).is_err());
+        assert!(history.try_set(&Loc::new(2, 3), Some(3)).is_err());
+        assert_eq!(history.edits().len(), 3);
+        let end = history.board().clone();
+        assert_eq!(end.marks(&Loc::new(4, 1)), Some(&vec![1, 4]));
+
+        assert!(matches!(history.undo(), Some(Edit::Marks { .. })));
+        assert_eq!(history.board().marks(&Loc::new(4, 1)), None);
+        history.undo();
+        assert_eq!(history.board().value(&Loc::new(1, 2)), None);
+        assert_eq!(history.board().value(&Loc::new(2, 3)), Some(1));
+        history.undo();
+        assert_eq!(history.board(), &start);
+        assert!(!history.can_undo());
+
+        while history.redo().is_some() {}
+        assert_eq!(history.board(), &end);
+
+        history.undo();
+        history.undo();
+        history.try_set(&Loc::new(1, 2), Some(2)).unwrap();
+        assert!(!history.can_redo());
+        assert_eq!(history.edits().len(), 2);
+    }
+
+    #[test]
+    fn test_history_checkpoints() {
+        let mut history = history();
+        history.set(&Loc::new(1, 2), Some(2)).unwrap();
+        history.checkpoint("guess");
+        history.force_set(&Loc::new(2, 3), Some(1), Origin::Solved);
+        history.force_set(&Loc::new(4, 1), Some(4), Origin::Solved);
+        assert!(history.board().is_solved());
+
+        history.restore("guess").unwrap();
+        assert_eq!(history.board().value(&Loc::new(2, 3)), None);
+        assert_eq!(history.board().value(&Loc::new(1, 2)), Some(2));
+        assert!(history.restore("other").is_err());
+
+        history.undo();
+        history.checkpoint("start");
+        history.set(&Loc::new(2, 3), Some(1)).unwrap();
+        assert_eq!(history.checkpoints(), vec!["start"]);
+        assert!(history.restore("guess").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_history_serde() {
+        let mut history = history();
+        history.set(&Loc::new(1, 2), Some(2)).unwrap();
+        history.checkpoint("guess");
+        history.force_set(&Loc::new(2, 3), Some(1), Origin::Solved);
+        history.undo();
+
+        let json = serde_json::to_string(&history).unwrap();
+        let mut read: History = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, history);
+        read.redo();
+        assert_eq!(read.board().origin(&Loc::new(2, 3)), Some(Origin::Solved));
+
+        let replayed = History::replay(history.board().givens(), read.edits()).unwrap();
+        assert_eq!(replayed.board(), read.board());
+
+        let broken = json.replace("\"position\":1", "\"position\":7");
+        assert!(serde_json::from_str::<History>(&broken).is_err());
+        let broken = json.replace("[1,\"solved\"]", "[99,\"solved\"]");
+        assert_ne!(broken, json);
+        assert!(serde_json::from_str::<History>(&broken).is_err());
+
+        let edit = Edit::Value {
+            loc: Loc::new(1, 2),
+            before: None,
+            after: Some((99, Origin::Entered)),
+        };
+        assert!(History::replay(history.board().givens(), &[edit]).is_err());
+        let edit = Edit::Value {
+            loc: Loc::new(1, 2),
+            before: Some((0, Origin::Entered)),
+            after: None,
+        };
+        assert!(History::replay(history.board().givens(), &[edit]).is_err());
+    }
+}
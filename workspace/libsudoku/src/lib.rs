@@ -1,15 +1,52 @@
-use std::collections::HashMap;
-use std::ops::Deref;
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use core::ops::Deref;
+#[cfg(feature = "serde")]
+use serde::de::{self, Deserialize, Deserializer};
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+#[cfg(feature = "json")]
 use serde_json::Value;
+use prelude::*;
+/// Items of the standard prelude, taken from `alloc` so that the crate also
+/// builds without `std`.
+mod prelude {
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
 pub mod loc;
+pub mod constraint;
+pub mod multigrid;
+pub mod symbols;
+pub mod formats;
+#[cfg(feature = "std")]
+pub mod binary;
+pub mod svg;
+pub mod pdf;
+pub mod ansi;
+#[cfg(feature = "serde")]
+pub mod state;
+pub mod history;
+pub mod search;
+#[cfg(feature = "std")]
+pub mod batch;
+mod drawing;
+mod line;
+mod pencil;
 mod checker;
 mod verifier;
-use loc::Loc;
+use loc::{Loc, L};
+use constraint::{Constraint, Constraints};
+use symbols::SymbolSet;
 use verifier::Verifier;
 
 
-impl From<(u8, &BoardMap)> for Verifier {
-    fn from(other: (u8, &BoardMap)) -> Self {
+impl From<(L, &BoardMap)> for Verifier {
+    fn from(other: (L, &BoardMap)) -> Self {
         let (size, map) = other;
         let mut s = Self::new(size);
         for (loc, value) in map {
@@ -21,93 +58,174 @@ impl From<(u8, &BoardMap)> for Verifier {
     }
 }
 
-type BoardMap = HashMap<Loc, Option<u8>>;
+type BoardMap = BTreeMap<Loc, Option<L>>;
+
+/// Who placed the digit of a filled cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Origin {
+    /// Clue of the puzzle, which `Board::set` refuses to change.
+    Given,
+    /// Digit placed by the player.
+    Entered,
+    /// Digit placed by the solver.
+    Solved,
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Board {
-    size: u8,
-    top: u8,
+    size: L,
+    top: L,
     values: BoardMap,
     verification: Verifier,
+    constraints: Constraints,
+    symbols: SymbolSet,
+    marks: BTreeMap<Loc, Vec<L>>,
+    origins: BTreeMap<Loc, Origin>,
 }
 
 impl Board {
-    fn calc_top(n: u8) -> u8 {
-        n.pow(2)
+    /// Number of rows of a board with boxes of `n` rows, if it fits `L`.
+    fn calc_top(n: L) -> Option<L> {
+        n.checked_pow(2)
+    }
+
+    fn calc_size(top: usize) -> Option<L> {
+        (1..=L::from(u8::MAX))
+            .take_while(|s| usize::from(*s).pow(2) <= top)
+            .find(|s| usize::from(*s).pow(2) == top)
     }
 
-    fn gen_all_locs(top: u8) -> Vec<Loc> {
+    fn gen_all_locs(top: L) -> Vec<Loc> {
         let rn = 1..=top;
         rn.clone()
             .flat_map(|x| rn.clone().map(move |y| Loc::new(x, y)))
             .collect()
     }
 
-    fn init_values(top: u8) -> BoardMap {
-        let mut values = HashMap::new();
-        values.reserve(top.pow(2).into());
+    fn init_values(top: L) -> BoardMap {
+        let mut values = BTreeMap::new();
         for l in Board::gen_all_locs(top) {
             values.insert(l, None);
         }
         values
     }
 
-    pub fn new(size: u8) -> Self {
-        let top = Board::calc_top(size);
+    /// Empty board with boxes of `size` rows. Panics for sizes above 255,
+    /// which do not fit `L`.
+    pub fn new(size: L) -> Self {
+        let top = Board::calc_top(size).expect("Board size must be at most 255");
         let values = Board::init_values(top);
         Self {
             size,
             top,
             values,
             verification: Verifier::new(size),
+            constraints: Constraints::default(),
+            symbols: SymbolSet::numeric(top),
+            marks: BTreeMap::new(),
+            origins: BTreeMap::new(),
+        }
+    }
+
+    pub fn size(&self) -> L {
+        self.size
+    }
+
+    pub fn top(&self) -> L {
+        self.top
+    }
+
+    pub fn value(&self, loc: &Loc) -> Option<L> {
+        self.values.get(loc).copied().flatten()
+    }
+
+    pub fn add_constraint(&mut self, constraint: impl Constraint + 'static) {
+        self.constraints.push(Arc::new(constraint));
+    }
+
+    pub fn constraints(&self) -> &Constraints {
+        &self.constraints
+    }
+
+    pub fn symbols(&self) -> &SymbolSet {
+        &self.symbols
+    }
+
+    pub fn set_symbols(&mut self, symbols: SymbolSet) -> Result<(), String> {
+        if symbols.len() != usize::from(self.top) {
+            return Err(format!(
+                "Alphabet has {} symbols instead of {}",
+                symbols.len(),
+                self.top
+            ));
         }
+        self.symbols = symbols;
+        Ok(())
     }
 
     pub fn is_solved(&self) -> bool {
         self.verification.is_solved()
+            && self.constraints.iter().all(|c| c.is_satisfied(self))
     }
 
     pub fn get_str(&self, loc: &Loc) -> String {
         if let Some(v) = self.values.get(loc).unwrap() {
-            v.to_string()
+            match self.symbols.symbol(*v) {
+                Some(s) => s.to_string(),
+                None => v.to_string(),
+            }
         } else {
             "■".to_string()
         }
     }
 
-    fn draw_upper_bar(size: u8) -> String {
+    fn cell_width(&self) -> usize {
+        self.symbols.width().max(1)
+    }
+
+    fn draw_upper_bar(size: L, width: usize) -> String {
         let size = usize::from(size);
-        let sqr = vec![vec!["═══"; size].join("═"); size].join("╦");
+        let cell = "═".repeat(width + 2);
+        let sqr = vec![vec![cell; size].join("═"); size].join("╦");
         format!("╔{}╗\n", sqr)
     }
 
-    fn draw_lower_bar(size: u8) -> String {
+    fn draw_lower_bar(size: L, width: usize) -> String {
         let size = usize::from(size);
-        let sqr = vec![vec!["═══"; size].join("═"); size].join("╩");
+        let cell = "═".repeat(width + 2);
+        let sqr = vec![vec![cell; size].join("═"); size].join("╩");
         format!("╚{}╝\n", sqr)
     }
 
-    fn draw_middle_bar(size: u8) -> String {
+    fn draw_middle_bar(size: L, width: usize) -> String {
         let size = usize::from(size);
-        let sqr = vec![vec!["═══"; size].join("═"); size].join("╬");
+        let cell = "═".repeat(width + 2);
+        let sqr = vec![vec![cell; size].join("═"); size].join("╬");
         format!("╠{}╣\n", sqr)
     }
 
-    fn draw_middle_soft(size: u8) -> String {
+    fn draw_middle_soft(size: L, width: usize) -> String {
         let size = usize::from(size);
-        let sqr = vec![vec!["───"; size].join("┼"); size].join("║");
+        let cell = "─".repeat(width + 2);
+        let sqr = vec![vec![cell; size].join("┼"); size].join("║");
         format!("║{}║\n", sqr)
     }
 
     pub fn draw(&self) -> String {
         let mut output = String::new();
+        let width = self.cell_width();
 
-        output.push_str(&Board::draw_upper_bar(self.size));
+        output.push_str(&Board::draw_upper_bar(self.size, width));
         for row in 1..=self.top {
-            let mut temp: Vec<String> = Vec::new();
-            temp.reserve(usize::from(self.top) * 2);
+            let mut temp: Vec<String> = Vec::with_capacity(usize::from(self.top) * 2);
             for col in 1..=self.top {
-                temp.push(format!(" {} ", self.get_str(&Loc::new(row, col))));
+                temp.push(format!(
+                    " {:>width$} ",
+                    self.get_str(&Loc::new(row, col)),
+                    width = width
+                ));
                 if col % self.size != 0 {
                     temp.push("│".to_string());
                 } else {
@@ -117,17 +235,17 @@ impl Board {
             output.push_str(&format!("║{}║\n", temp[..temp.len() - 1].join("")));
             if row < self.top {
                 if row % self.size == 0 {
-                    output.push_str(&Board::draw_middle_bar(self.size));
+                    output.push_str(&Board::draw_middle_bar(self.size, width));
                 } else {
-                    output.push_str(&Board::draw_middle_soft(self.size));
+                    output.push_str(&Board::draw_middle_soft(self.size, width));
                 }
             }
         }
-        output.push_str(&Board::draw_lower_bar(self.size));
+        output.push_str(&Board::draw_lower_bar(self.size, width));
         output
     }
 
-    pub fn to_vec(&self) -> Vec<Vec<Option<u8>>> {
+    pub fn to_vec(&self) -> Vec<Vec<Option<L>>> {
         let mut result = vec![vec![None; self.top.into()]; self.top.into()];
         for (loc, value) in self.iter() {
             if let Some(v) = value {
@@ -138,35 +256,165 @@ impl Board {
         result
     }
 
-    pub fn available_values(&self, loc: &Loc) -> Vec<u8> {
-        self.verification.available_values(loc)
+    pub fn available_values(&self, loc: &Loc) -> Vec<L> {
+        let mut values = self.verification.available_values(loc);
+        for constraint in self.constraints.iter() {
+            if values.is_empty() {
+                break;
+            }
+            let eliminated = constraint.eliminate(self, loc);
+            values.retain(|v| !eliminated.contains(v));
+        }
+        values
+    }
+
+    /// Cells sharing a row, column or box with `loc`, excluding itself.
+    pub fn peers(&self, loc: &Loc) -> Vec<Loc> {
+        let sqr = loc.sqr(self.size);
+        Board::gen_all_locs(self.top)
+            .into_iter()
+            .filter(|l| l != loc)
+            .filter(|l| l.row == loc.row || l.col == loc.col || l.sqr(self.size) == sqr)
+            .collect()
+    }
+
+    /// Filled cells holding the same value as one of their peers.
+    pub fn conflicts(&self) -> Vec<Loc> {
+        Board::gen_all_locs(self.top)
+            .into_iter()
+            .filter(|loc| {
+                self.value(loc).is_some_and(|v| {
+                    self.peers(loc)
+                        .iter()
+                        .any(|p| self.value(p) == Some(v))
+                })
+            })
+            .collect()
+    }
+
+    /// Candidates of an empty cell, narrowed down by its pencil marks if any
+    /// were set. Filled cells have no candidates.
+    pub fn candidates(&self, loc: &Loc) -> Vec<L> {
+        if self.value(loc).is_some() {
+            return Vec::new();
+        }
+        let mut values = self.available_values(loc);
+        if let Some(marks) = self.marks.get(loc) {
+            values.retain(|v| marks.contains(v));
+        }
+        values
+    }
+
+    pub fn marks(&self, loc: &Loc) -> Option<&Vec<L>> {
+        self.marks.get(loc)
+    }
+
+    /// Sets explicit pencil marks of a cell, or clears them with `None`.
+    pub fn set_marks(&mut self, loc: &Loc, marks: Option<Vec<L>>) {
+        match marks {
+            Some(mut marks) => {
+                marks.retain(|v| (1..=self.top).contains(v));
+                marks.sort();
+                marks.dedup();
+                self.marks.insert(*loc, marks);
+            }
+            None => {
+                self.marks.remove(loc);
+            }
+        }
+    }
+
+    pub fn origin(&self, loc: &Loc) -> Option<Origin> {
+        self.origins.get(loc).copied()
+    }
+
+    /// Board holding only the givens of this one.
+    pub fn givens(&self) -> Self {
+        let mut board = Board::new(self.size);
+        board.constraints = self.constraints.clone();
+        board.symbols = self.symbols.clone();
+        for (loc, origin) in &self.origins {
+            if *origin == Origin::Given {
+                board.force_set(loc, self.value(loc), Origin::Given);
+            }
+        }
+        board
+    }
+
+    /// Places a digit entered by the player, or clears a cell with `None`,
+    /// without checking the rules. Givens, cells outside of the board and
+    /// digits out of range are refused.
+    pub fn set(&mut self, loc: &Loc, value: Option<L>) -> Result<(), String> {
+        self.check_editable(loc, value)?;
+        self.force_set(loc, value, Origin::Entered);
+        Ok(())
+    }
+
+    fn check_editable(&self, loc: &Loc, value: Option<L>) -> Result<(), String> {
+        if !self.contains_key(loc) {
+            return Err(format!("{} is outside of the board", loc));
+        }
+        if let Some(v) = value.filter(|v| !(1..=self.top).contains(v)) {
+            return Err(format!("'{}' is not a digit between 1 and {}", v, self.top));
+        }
+        match self.origin(loc) {
+            Some(Origin::Given) => Err(format!("Cannot change the given in {}", loc)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Marks `value` as used again in the units of `loc` where a peer still
+    /// holds it, after a conflicting copy was removed from `loc`.
+    fn restore_peers(&mut self, loc: &Loc, value: L) {
+        let (size, top) = (self.size, self.top);
+        let (row, col) = ((loc.row - 1) / size * size, (loc.col - 1) / size * size);
+        for i in 1..=top {
+            let square = Loc::new(row + (i - 1) / size + 1, col + (i - 1) % size + 1);
+            for peer in [Loc::new(loc.row, i), Loc::new(i, loc.col), square] {
+                if peer != *loc && self.value(&peer) == Some(value) {
+                    self.verification.set(&peer, value);
+                }
+            }
+        }
     }
 
-    pub fn set(&mut self, loc: &Loc, value: Option<u8>) {
+    /// Sets a cell regardless of its current origin, e.g. to place givens.
+    /// Panics for cells outside of the board or digits out of range, which
+    /// `Board::set` refuses.
+    pub fn force_set(&mut self, loc: &Loc, value: Option<L>, origin: Origin) {
         let current = self.values.get_mut(loc).unwrap();
 
-        if let Some(old) = current {
-            self.verification.unset(loc, *old);
-            *current = None;
+        if let Some(old) = current.take() {
+            self.verification.unset(loc, old);
+            self.origins.remove(loc);
+            self.restore_peers(loc, old);
         }
 
         if let Some(new) = value {
-            *current = Some(new);
+            self.values.insert(*loc, Some(new));
             self.verification.set(loc, new);
+            self.origins.insert(*loc, origin);
         }
     }
 
-    pub fn try_set(&mut self, loc: &Loc, value: Option<u8>) -> Result<(), String> {
-        if value.is_some() && !self.available_values(loc).contains(&value.unwrap()) {
-            return Err(format!(
-                "'{}' cannot be inserted in {:?}",
-                value.unwrap(),
-                loc
-            ));
+    /// Empties a cell filled by a search. Searches only place available
+    /// digits, so no peer can hold the same one and none needs restoring.
+    pub(crate) fn unfill(&mut self, loc: &Loc) {
+        if let Some(old) = self.values.insert(*loc, None).flatten() {
+            self.verification.unset(loc, old);
+            self.origins.remove(loc);
         }
+    }
 
-        self.set(loc, value);
-        Ok(())
+    pub fn try_set(&mut self, loc: &Loc, value: Option<L>) -> Result<(), String> {
+        self.check_editable(loc, value)?;
+        if let Some(v) = value {
+            if !self.available_values(loc).contains(&v) {
+                return Err(format!("'{}' cannot be inserted in {}", v, loc));
+            }
+        }
+
+        self.set(loc, value)
     }
 
     fn try_to_solve(&mut self, locs: &[Loc]) -> bool {
@@ -178,11 +426,11 @@ impl Board {
         let rest = &locs[1..];
 
         for value in self.available_values(loc) {
-            self.set(loc, Some(value));
+            self.force_set(loc, Some(value), Origin::Solved);
             if self.try_to_solve(rest) {
                 return true;
             }
-            self.set(loc, None);
+            self.unfill(loc);
         }
 
         false
@@ -223,13 +471,25 @@ impl Board {
             None
         }
     }
+
+    /// Suggests the digit of the empty cell with the fewest candidates, taken
+    /// from the solution of the givens so that wrong entries do not mislead.
+    pub fn hint(&self) -> Option<(Loc, L)> {
+        let solution = self.givens().solve()?;
+        let loc = self
+            .iter()
+            .filter(|(_, value)| value.is_none())
+            .map(|(loc, _)| *loc)
+            .min_by_key(|loc| (self.available_values(loc).len(), *loc))?;
+        Some((loc, solution.value(&loc)?))
+    }
 }
 
-impl From<(u8, BoardMap)> for Board {
-    fn from(other: (u8, BoardMap)) -> Self {
+impl From<(L, BoardMap)> for Board {
+    fn from(other: (L, BoardMap)) -> Self {
         let (size, values) = other;
-        let top = Board::calc_top(size);
-        let expected_len = usize::from(top.pow(2));
+        let top = Board::calc_top(size).expect("Board size must be at most 255");
+        let expected_len = usize::from(top).pow(2);
         assert_eq!(
             values.len(),
             expected_len,
@@ -243,12 +503,21 @@ impl From<(u8, BoardMap)> for Board {
         assert!(keys.into_iter().eq(Board::gen_all_locs(top)));
 
         let verification = Verifier::from((size, &values));
+        let origins = values
+            .iter()
+            .filter(|(_, v)| v.is_some())
+            .map(|(loc, _)| (*loc, Origin::Given))
+            .collect();
 
         Self {
             size,
             top,
             values,
             verification,
+            constraints: Constraints::default(),
+            symbols: SymbolSet::numeric(top),
+            marks: BTreeMap::new(),
+            origins,
         }
     }
 }
@@ -261,8 +530,7 @@ impl Deref for Board {
     }
 }
 
-use serde::ser::{Serialize, SerializeSeq, Serializer};
-
+#[cfg(feature = "serde")]
 impl Serialize for Board {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -270,14 +538,22 @@ impl Serialize for Board {
     {
         let mut seq = serializer.serialize_seq(Some(self.top as usize))?;
         for element in self.to_vec() {
-            seq.serialize_element(&element)?;
+            if self.symbols.is_numeric() {
+                seq.serialize_element(&element)?;
+            } else {
+                let symbols: Vec<Option<&str>> = element
+                    .into_iter()
+                    .map(|v| v.and_then(|v| self.symbols.symbol(v)))
+                    .collect();
+                seq.serialize_element(&symbols)?;
+            }
         }
         seq.end()
     }
 }
 
-impl From<&Board> for Vec<Vec<Option<u8>>> {
-    fn from(other: &Board) -> Vec<Vec<Option<u8>>> {
+impl From<&Board> for Vec<Vec<Option<L>>> {
+    fn from(other: &Board) -> Vec<Vec<Option<L>>> {
         let mut result = vec![vec![None; other.top.into()]; other.top.into()];
         for (loc, value) in other.iter() {
             if let Some(v) = value {
@@ -289,46 +565,222 @@ impl From<&Board> for Vec<Vec<Option<u8>>> {
     }
 }
 
-impl From<Value> for Board {
-    fn from(other: Value) -> Self {
-        let rows = match other.as_array() {
-            Some(a) => a,
-            _ => panic!("Could convert to array - {}", other),
+/// Cell of a board read through serde, before its alphabet is known.
+#[cfg(feature = "serde")]
+enum RawCell {
+    Blank,
+    Number(u64),
+    Symbol(String),
+}
+
+#[cfg(feature = "serde")]
+impl From<&str> for RawCell {
+    fn from(other: &str) -> Self {
+        match other {
+            "" | "." => RawCell::Blank,
+            symbol => RawCell::Symbol(symbol.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for RawCell {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CellVisitor;
+
+        impl<'de> de::Visitor<'de> for CellVisitor {
+            type Value = RawCell;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("a digit, a symbol or a blank cell")
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<RawCell, E> {
+                Ok(RawCell::Blank)
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<RawCell, E> {
+                Ok(RawCell::Blank)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, other: D) -> Result<RawCell, D::Error> {
+                RawCell::deserialize(other)
+            }
+
+            fn visit_u64<E: de::Error>(self, other: u64) -> Result<RawCell, E> {
+                Ok(RawCell::Number(other))
+            }
+
+            fn visit_i64<E: de::Error>(self, other: i64) -> Result<RawCell, E> {
+                u64::try_from(other)
+                    .map(RawCell::Number)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(other), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, other: &str) -> Result<RawCell, E> {
+                Ok(RawCell::from(other))
+            }
+        }
+
+        deserializer.deserialize_any(CellVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Board {
+    /// Builds a board from rows of cells, guessing the alphabet of symbol
+    /// cells from `SymbolSet::presets`.
+    fn from_cells(rows: &[Vec<RawCell>]) -> Result<Self, String> {
+        let top = L::try_from(rows.len()).map_err(|e| e.to_string())?;
+        let mut error = None;
+        for symbols in SymbolSet::presets(top) {
+            match Board::from_cells_with(rows, &symbols) {
+                Ok(board) => return Ok(board),
+                Err(e) => error = error.or(Some(e)),
+            }
+        }
+        Err(error.unwrap_or(format!("{} rows do not form a square board", rows.len())))
+    }
+
+    /// Fails on the first given repeating a digit of an earlier peer.
+    fn check_givens(&self) -> Result<(), String> {
+        let mut placed = Board::new(self.size);
+        for (loc, value) in self.iter() {
+            if let Some(v) = *value {
+                if !placed.available_values(loc).contains(&v) {
+                    return Err(format!("Given '{}' in {} repeats a peer", v, loc));
+                }
+                placed.force_set(loc, Some(v), Origin::Given);
+            }
+        }
+        Ok(())
+    }
+
+    fn from_cells_with(rows: &[Vec<RawCell>], symbols: &SymbolSet) -> Result<Self, String> {
+        let size = match Board::calc_size(rows.len()) {
+            Some(s) => s,
+            _ => return Err(format!("{} rows do not form a square board", rows.len())),
         };
-        let size = f64::from(rows.len() as u8).sqrt() as u8;
-        if rows.len() == 9 {
-            assert_eq!(size, 3);
-        } else if rows.len() == 4 {
-            assert_eq!(size, 2)
-        } else if rows.len() == 1 {
-            assert_eq!(size, 1);
-        }
-        let mut values: HashMap<Loc, Option<u8>> = HashMap::new();
-
-        for (row, cols) in rows.iter().enumerate() {
-            let row = (row + 1) as u8;
-            for (col, v) in cols.as_array().unwrap().iter().enumerate() {
-                let col = (col + 1) as u8;
-                values.insert(
-                    Loc::new(row, col),
-                    if let serde_json::Value::Number(v) = v {
-                        Some(v.as_u64().unwrap() as u8)
-                    } else {
-                        None
+        let mut board = Board::new(size);
+        board.set_symbols(symbols.clone())?;
+
+        for (row, cols) in (1..=board.top).zip(rows.iter()) {
+            if cols.len() != rows.len() {
+                return Err(format!("Row {} is not an array of {} cells", row, rows.len()));
+            }
+            for (col, cell) in (1..=board.top).zip(cols.iter()) {
+                let loc = Loc::new(row, col);
+                let value = match cell {
+                    RawCell::Blank => None,
+                    RawCell::Symbol(s) => match symbols.value(s) {
+                        Some(v) => Some(v),
+                        None => return Err(format!("Unknown symbol '{}' in {}", s, loc)),
                     },
-                );
+                    RawCell::Number(n) => match L::try_from(*n) {
+                        Ok(v) if (1..=board.top).contains(&v) => Some(v),
+                        _ => return Err(format!("Invalid value {} in {}", n, loc)),
+                    },
+                };
+                board.force_set(&loc, value, Origin::Given);
             }
         }
-        Board::from((size, values))
+        Ok(board)
+    }
+}
+
+/// Rows of cells of nested JSON arrays.
+#[cfg(feature = "json")]
+fn json_cells(other: &Value) -> Result<Vec<Vec<RawCell>>, String> {
+    let rows = match other.as_array() {
+        Some(a) => a,
+        _ => return Err(format!("Could not convert to array - {}", other)),
+    };
+    let mut result = Vec::with_capacity(rows.len());
+    for (row, cols) in (1..).zip(rows.iter()) {
+        let cols = match cols.as_array() {
+            Some(c) => c,
+            _ => return Err(format!("Row {} is not an array of {} cells", row, rows.len())),
+        };
+        let mut cells = Vec::with_capacity(cols.len());
+        for (col, v) in (1..).zip(cols.iter()) {
+            cells.push(match v {
+                Value::Null => RawCell::Blank,
+                Value::String(s) => RawCell::from(s.as_str()),
+                Value::Number(n) => match n.as_u64() {
+                    Some(n) => RawCell::Number(n),
+                    None => return Err(format!("Invalid value {} in r{}c{}", n, row, col)),
+                },
+                _ => return Err(format!("Invalid cell {} in r{}c{}", v, row, col)),
+            });
+        }
+        result.push(cells);
+    }
+    Ok(result)
+}
+
+#[cfg(feature = "json")]
+impl Board {
+    /// Reads a board from nested JSON arrays, guessing the alphabet of symbol
+    /// cells from `SymbolSet::presets`, and rejecting givens that repeat a peer
+    /// like `Deserialize for Board`.
+    pub fn from_json(other: &Value) -> Result<Self, String> {
+        let board = Board::from_json_unchecked(other)?;
+        board.check_givens()?;
+        Ok(board)
+    }
+
+    /// Like `Board::from_json`, but keeps givens repeating a peer, e.g. to
+    /// report them with `Board::conflicts`.
+    pub fn from_json_unchecked(other: &Value) -> Result<Self, String> {
+        Board::from_cells(&json_cells(other)?)
+    }
+
+    /// Reads a board from nested JSON arrays, where cells are either numbers,
+    /// symbols from `symbols`, or `null`, `""` and `"."` for blanks.
+    pub fn from_json_with(other: &Value, symbols: &SymbolSet) -> Result<Self, String> {
+        Board::from_cells_with(&json_cells(other)?, symbols)
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Accepts the same nested arrays as `Board::from_json` from any
+/// self-describing format, rejecting boards that are not square, hold values
+/// out of range or repeat a given within a row, column or box.
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rows = Vec::<Vec<RawCell>>::deserialize(deserializer)?;
+        let board = Board::from_cells(&rows).map_err(de::Error::custom)?;
+        board.check_givens().map_err(de::Error::custom)?;
+        Ok(board)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<Value> for Board {
+    fn from(other: Value) -> Self {
+        let top = other.as_array().map_or(0, Vec::len);
+        let symbols = SymbolSet::numeric(L::try_from(top).unwrap_or(0));
+        match Board::from_json_with(&other, &symbols) {
+            Ok(board) => board,
+            Err(e) => panic!("{}", e),
+        }
     }
 }
 
+#[cfg(feature = "json")]
 impl From<&Board> for serde_json::Value {
     fn from(other: &Board) -> serde_json::Value {
         serde_json::json!(other)
     }
 }
 
+#[cfg(feature = "json")]
 impl From<&str> for Board {
     fn from(other: &str) -> Self {
         Self::from(serde_json::from_str::<Value>(other).unwrap())
@@ -372,8 +824,12 @@ mod tests {
             Board {
                 size: 1,
                 top: 1,
-                values: HashMap::from([(Loc::new(1, 1), None)]),
-                verification: Verifier::new(1)
+                values: BTreeMap::from([(Loc::new(1, 1), None)]),
+                verification: Verifier::new(1),
+                constraints: Constraints::default(),
+                symbols: SymbolSet::numeric(1),
+                marks: BTreeMap::new(),
+                origins: BTreeMap::new(),
             }
         );
         assert_eq!(
@@ -381,7 +837,7 @@ mod tests {
             Board {
                 size: 2,
                 top: 4,
-                values: HashMap::from([
+                values: BTreeMap::from([
                     (Loc::new(1, 1), None),
                     (Loc::new(1, 2), None),
                     (Loc::new(1, 3), None),
@@ -399,7 +855,11 @@ mod tests {
                     (Loc::new(4, 3), None),
                     (Loc::new(4, 4), None),
                 ]),
-                verification: Verifier::new(2)
+                verification: Verifier::new(2),
+                constraints: Constraints::default(),
+                symbols: SymbolSet::numeric(4),
+                marks: BTreeMap::new(),
+                origins: BTreeMap::new(),
             }
         );
     }
@@ -408,13 +868,13 @@ mod tests {
     fn test_board_from() {
         assert_eq!(
             Board::new(1),
-            Board::from((1, HashMap::from([(Loc::new(1, 1), None)])))
+            Board::from((1, BTreeMap::from([(Loc::new(1, 1), None)])))
         );
         assert_eq!(
             Board::new(2),
             Board::from((
                 2,
-                HashMap::from([
+                BTreeMap::from([
                     (Loc::new(1, 1), None),
                     (Loc::new(1, 2), None),
                     (Loc::new(1, 3), None),
@@ -437,6 +897,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "json")]
     fn test_board_is_solved() {
         let boards = [
             Board::from("[[1]]"),
@@ -456,10 +917,10 @@ mod tests {
     #[test]
     fn test_board_is_solved_false() {
         let boards = [
-            Board::from((1, HashMap::from([(Loc::new(1, 1), None)]))),
+            Board::from((1, BTreeMap::from([(Loc::new(1, 1), None)]))),
             Board::from((
                 2,
-                HashMap::from([
+                BTreeMap::from([
                     (Loc::new(1, 1), Some(1)),
                     (Loc::new(1, 2), Some(2)),
                     (Loc::new(1, 3), Some(3)),
@@ -490,11 +951,12 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "json")]
     fn test_board_from_json() {
         assert_eq!(
             Board::from((
                 2,
-                HashMap::from([
+                BTreeMap::from([
                     (Loc::new(1, 1), Some(1)),
                     (Loc::new(1, 2), Some(2)),
                     (Loc::new(1, 3), Some(3)),
@@ -518,6 +980,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "json")]
     fn test_board_available_values() {
         let tests = [
             (Board::from("[[null]]"), Loc::new(1, 1), vec![1]),
@@ -560,6 +1023,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "json")]
     fn test_board_set_none() {
         let tests = [
             (
@@ -574,38 +1038,57 @@ mod tests {
                 None,
                 Board::from("[[null]]"),
             ),
-            (
-                Board::from("[[1]]"),
-                Loc::new(1, 1),
-                Some(1),
-                Board::from("[[1]]"),
-            ),
-            (
-                Board::from("[[1]]"),
-                Loc::new(1, 1),
-                None,
-                Board::from("[[null]]"),
-            ),
         ];
 
         for (mut initial, loc, value, expected) in tests {
-            initial.set(&loc, value);
-            assert_eq!(initial, expected)
+            initial.set(&loc, value).unwrap();
+            assert_eq!(initial.to_vec(), expected.to_vec())
         }
+
+        let mut given = Board::from("[[1]]");
+        assert!(given.set(&Loc::new(1, 1), None).is_err());
+        assert!(given.set(&Loc::new(1, 1), Some(1)).is_err());
+        assert_eq!(given, Board::from("[[1]]"));
+        given.force_set(&Loc::new(1, 1), None, Origin::Given);
+        assert_eq!(given, Board::from("[[null]]"));
+    }
+
+    #[test]
+    fn test_board_origins() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        assert_eq!(board.origin(&Loc::new(1, 1)), Some(Origin::Given));
+        assert_eq!(board.origin(&Loc::new(1, 2)), None);
+
+        board.set(&Loc::new(1, 2), Some(2)).unwrap();
+        assert_eq!(board.origin(&Loc::new(1, 2)), Some(Origin::Entered));
+        assert!(board.try_set(&Loc::new(1, 1), None).is_err());
+        assert_ne!(board, Board::from_line("123434.22143.321").unwrap());
+        assert_eq!(board.clone(), board);
+
+        let solved = board.solve().unwrap();
+        assert_eq!(solved.origin(&Loc::new(1, 1)), Some(Origin::Given));
+        assert_eq!(solved.origin(&Loc::new(1, 2)), Some(Origin::Entered));
+        assert_eq!(solved.origin(&Loc::new(2, 3)), Some(Origin::Solved));
+        assert_eq!(solved.givens(), Board::from_line("1.3434.22143.321").unwrap());
+
+        board.set(&Loc::new(1, 2), None).unwrap();
+        assert_eq!(board, Board::from_line("1.3434.22143.321").unwrap());
     }
 
     #[test]
+    #[cfg(feature = "json")]
     fn test_board_clone() {
         let initial = Board::from("[[1]]");
         let mut cloned = initial.clone();
 
         assert_eq!(initial, cloned);
 
-        cloned.set(&Loc::new(1, 1), None);
+        cloned.force_set(&Loc::new(1, 1), None, Origin::Given);
         assert_ne!(initial, cloned);
     }
 
     #[test]
+    #[cfg(feature = "json")]
     fn test_board_solve() {
         let tests = [
             (Board::from("[[null]]"), Board::from("[[1]]")),
@@ -621,8 +1104,8 @@ mod tests {
         for (initial, expected) in tests {
             if let Some(solved) = initial.solve() {
                 assert_eq!(
-                    solved,
-                    expected,
+                    solved.to_vec(),
+                    expected.to_vec(),
                     "Board was not solved properly:\n{}\n{}",
                     initial.draw(),
                     expected.draw()
@@ -632,4 +1115,328 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_board_constraints_available_values() {
+        let mut board = Board::from("[[null,null,3,4],[3,4,1,null],[null,1,4,3],[4,3,1,null]]");
+        assert_eq!(board.available_values(&Loc::new(1, 1)), vec![1, 2]);
+
+        board.add_constraint(constraint::Parity::even(vec![Loc::new(1, 1)]));
+        assert_eq!(board.available_values(&Loc::new(1, 1)), vec![2]);
+        assert!(board.try_set(&Loc::new(1, 1), Some(1)).is_err());
+        assert!(board.try_set(&Loc::new(1, 1), Some(2)).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_board_is_solved_constraints() {
+        let mut board = Board::from("[[1,2,3,4],[3,4,1,2],[2,1,4,3],[4,3,2,1]]");
+        assert!(board.is_solved());
+
+        board.add_constraint(constraint::AntiKing);
+        assert!(!board.is_solved());
+    }
+
+    #[test]
+    fn test_board_set_out_of_range() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        assert!(board.set(&Loc::new(1, 2), Some(9)).is_err());
+        assert!(board.set(&Loc::new(1, 2), Some(0)).is_err());
+        assert!(board.try_set(&Loc::new(1, 2), Some(9)).is_err());
+        assert_eq!(
+            board.set(&Loc::new(9, 9), None),
+            Err("r9c9 is outside of the board".to_string())
+        );
+        assert!(board.try_set(&Loc::new(9, 9), Some(1)).is_err());
+        assert_eq!(board.value(&Loc::new(1, 2)), None);
+    }
+
+    #[test]
+    fn test_board_clear_conflict() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        board.set(&Loc::new(1, 2), Some(4)).unwrap();
+        board.set(&Loc::new(1, 2), Some(2)).unwrap();
+        board.set(&Loc::new(2, 3), Some(1)).unwrap();
+        board.set(&Loc::new(4, 1), Some(4)).unwrap();
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn test_board_hint() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        assert_eq!(board.hint(), Some((Loc::new(1, 2), 2)));
+        board.set(&Loc::new(1, 2), Some(4)).unwrap();
+        assert_eq!(board.hint(), Some((Loc::new(2, 3), 1)));
+        assert_eq!(board.solve(), None);
+        assert_eq!(board.givens().solve().unwrap().hint(), None);
+        assert_eq!(Board::from_line("11..............").unwrap().hint(), None);
+    }
+
+    #[test]
+    fn test_board_solve_constraints() {
+        let mut board = Board::new(2);
+        board.add_constraint(constraint::Parity::even(vec![Loc::new(1, 1), Loc::new(2, 3)]));
+        board.add_constraint(constraint::Kropki::new(
+            Loc::new(1, 1),
+            Loc::new(1, 2),
+            constraint::Dot::White,
+        ));
+        board.add_constraint(constraint::Thermometer::new(vec![
+            Loc::new(4, 1),
+            Loc::new(4, 2),
+            Loc::new(4, 3),
+        ]));
+
+        let solved = board.solve().expect("board should be solvable");
+        assert!(solved.is_solved());
+        assert_eq!(solved.constraints().len(), 3);
+        assert_eq!(solved.value(&Loc::new(1, 1)).unwrap() % 2, 0);
+        assert_eq!(solved.value(&Loc::new(2, 3)).unwrap() % 2, 0);
+        assert_eq!(
+            solved
+                .value(&Loc::new(1, 1))
+                .unwrap()
+                .abs_diff(solved.value(&Loc::new(1, 2)).unwrap()),
+            1
+        );
+        assert!(solved.value(&Loc::new(4, 1)) < solved.value(&Loc::new(4, 2)));
+        assert!(solved.value(&Loc::new(4, 2)) < solved.value(&Loc::new(4, 3)));
+    }
+
+    #[test]
+    fn test_board_solve_constraints_unsolvable() {
+        let mut board = Board::new(2);
+        board.add_constraint(constraint::AntiKing);
+        assert_eq!(board.solve(), None);
+    }
+
+    fn gen_pattern(size: L) -> Board {
+        let mut board = Board::new(size);
+        let top = board.top();
+        for loc in Board::gen_all_locs(top) {
+            let (r, c) = (loc.row - 1, loc.col - 1);
+            let value = (size * (r % size) + r / size + c) % top + 1;
+            board.force_set(&loc, Some(value), Origin::Given);
+        }
+        board
+    }
+
+    #[test]
+    fn test_board_calc_size() {
+        assert_eq!(Board::calc_size(1), Some(1));
+        assert_eq!(Board::calc_size(4), Some(2));
+        assert_eq!(Board::calc_size(9), Some(3));
+        assert_eq!(Board::calc_size(25), Some(5));
+        assert_eq!(Board::calc_size(36), Some(6));
+        assert_eq!(Board::calc_size(0), None);
+        assert_eq!(Board::calc_size(10), None);
+        assert_eq!(Board::calc_top(255), Some(65025));
+        assert_eq!(Board::calc_top(256), None);
+    }
+
+    #[test]
+    fn test_board_large() {
+        for size in [4, 5, 6] {
+            let mut board = gen_pattern(size);
+            let top = board.top();
+            assert!(board.is_solved());
+            assert_eq!(board.len(), usize::from(top).pow(2));
+
+            let removed = [Loc::new(1, 1), Loc::new(top, top), Loc::new(top / 2, 3)];
+            let expected = board.clone();
+            for loc in removed.iter() {
+                board.force_set(loc, None, Origin::Given);
+            }
+            assert!(!board.is_solved());
+            assert_eq!(board.solve().map(|b| b.to_vec()), Some(expected.to_vec()));
+        }
+    }
+
+    #[test]
+    fn test_board_draw_wide() {
+        let mut board = Board::new(4);
+        board.set(&Loc::new(1, 1), Some(16)).unwrap();
+        board.set(&Loc::new(1, 2), Some(7)).unwrap();
+        let drawing = board.draw();
+        let lines: Vec<&str> = drawing.lines().collect();
+        assert_eq!(lines.len(), 33);
+        assert!(lines[1].starts_with("║ 16 │  7 │  ■ │  ■ ║"));
+        assert!(lines
+            .iter()
+            .all(|l| l.chars().count() == lines[0].chars().count()));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    #[should_panic]
+    fn test_board_from_json_not_square() {
+        let _ = Board::from("[[1,2],[2,1]]");
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_board_symbols() {
+        let mut board = Board::from("[[1,null,3,4],[3,4,1,2],[2,1,4,3],[4,3,2,1]]");
+        assert!(board.set_symbols(SymbolSet::hex(16).unwrap()).is_err());
+        assert!(board.set_symbols(SymbolSet::letters(4).unwrap()).is_ok());
+
+        assert_eq!(board.get_str(&Loc::new(1, 1)), "A");
+        assert_eq!(board.get_str(&Loc::new(1, 2)), "■");
+        assert!(board.draw().contains("║ A │ ■ ║ C │ D ║"));
+        assert_eq!(
+            serde_json::Value::from(&board),
+            serde_json::json!([
+                ["A", null, "C", "D"],
+                ["C", "D", "A", "B"],
+                ["B", "A", "D", "C"],
+                ["D", "C", "B", "A"]
+            ])
+        );
+
+        let solved = board.solve().unwrap();
+        assert_eq!(solved.symbols(), board.symbols());
+        assert_eq!(solved.get_str(&Loc::new(1, 2)), "B");
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_board_from_json_with() {
+        let symbols = SymbolSet::custom(
+            ["★", "♥", "♦", "♣"].iter().map(|s| s.to_string()).collect(),
+        )
+        .unwrap();
+        let value = serde_json::json!([
+            ["★", "", "♦", "♣"],
+            ["♦", "♣", ".", "♥"],
+            [2, 1, 4, 3],
+            [null, "♦", "♥", "★"]
+        ]);
+        let board = Board::from_json_with(&value, &symbols).unwrap();
+        assert_eq!(board.value(&Loc::new(1, 1)), Some(1));
+        assert_eq!(board.value(&Loc::new(1, 2)), None);
+        assert_eq!(board.value(&Loc::new(3, 1)), Some(2));
+        assert_eq!(board.get_str(&Loc::new(3, 1)), "♥");
+        assert_eq!(
+            Board::from_json_with(&serde_json::Value::from(&board), &symbols),
+            Ok(board)
+        );
+
+        let tests = [
+            serde_json::json!({}),
+            serde_json::json!([[1, 2], [2, 1]]),
+            serde_json::json!([["★", "x", "♦", "♣"], [null, null, null, null], [null, null, null, null], [null, null, null, null]]),
+            serde_json::json!([[5, null, null, null], [null, null, null, null], [null, null, null, null], [null, null, null, null]]),
+            serde_json::json!([[null, null, null], [null, null, null, null], [null, null, null, null], [null, null, null, null]]),
+            serde_json::json!([[true, null, null, null], [null, null, null, null], [null, null, null, null], [null, null, null, null]]),
+        ];
+        for value in tests {
+            assert!(Board::from_json_with(&value, &symbols).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_board_candidates() {
+        let mut board = Board::from("[[null,null,3,4],[3,4,1,null],[null,1,4,3],[4,3,1,null]]");
+        assert_eq!(board.candidates(&Loc::new(1, 1)), vec![1, 2]);
+        assert!(board.candidates(&Loc::new(1, 3)).is_empty());
+
+        board.set_marks(&Loc::new(1, 1), Some(vec![2, 2, 5, 0]));
+        assert_eq!(board.marks(&Loc::new(1, 1)), Some(&vec![2]));
+        assert_eq!(board.candidates(&Loc::new(1, 1)), vec![2]);
+        assert_eq!(board.available_values(&Loc::new(1, 1)), vec![1, 2]);
+
+        let cloned = board.clone();
+        assert_eq!(cloned.candidates(&Loc::new(1, 1)), vec![2]);
+        assert_ne!(cloned, Board::from("[[null,null,3,4],[3,4,1,null],[null,1,4,3],[4,3,1,null]]"));
+
+        board.set_marks(&Loc::new(1, 1), None);
+        assert_eq!(board.marks(&Loc::new(1, 1)), None);
+        assert_eq!(board.candidates(&Loc::new(1, 1)), vec![1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_board_deserialize() {
+        let board = Board::from_line("1.3434.22143.321").unwrap();
+        let yaml = serde_yaml::to_string(&board).unwrap();
+        assert_eq!(serde_yaml::from_str::<Board>(&yaml).unwrap(), board);
+
+        let msgpack = rmp_serde::to_vec(&board).unwrap();
+        assert_eq!(rmp_serde::from_slice::<Board>(&msgpack).unwrap(), board);
+
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&board, &mut cbor).unwrap();
+        assert_eq!(ciborium::from_reader::<Board, _>(&cbor[..]).unwrap(), board);
+
+        #[derive(serde::Deserialize)]
+        struct Request {
+            board: Board,
+        }
+        let request: Request = toml::from_str(
+            r#"board = [[1, ".", 3, 4], [3, 4, ".", 2], [2, 1, 4, 3], [".", 3, 2, 1]]"#,
+        )
+        .unwrap();
+        assert_eq!(request.board, board);
+
+        let mut hex = Board::new(4);
+        hex.set_symbols(SymbolSet::hex(16).unwrap()).unwrap();
+        hex.force_set(&Loc::new(1, 1), Some(1), Origin::Given);
+        let yaml = serde_yaml::to_string(&hex).unwrap();
+        assert_eq!(serde_yaml::from_str::<Board>(&yaml).unwrap(), hex);
+
+        let broken = [
+            "{}",
+            "[[1, 2], [2, 1]]",
+            "[[5, null, null, null], [], [], []]",
+            "[[-1]]",
+            "[[true]]",
+            "[[1, 1, ., .], [., ., ., .], [., ., ., .], [., ., ., .]]",
+        ];
+        for yaml in broken {
+            assert!(serde_yaml::from_str::<Board>(yaml).is_err(), "{}", yaml);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_board_deserialize_json() {
+        let board = Board::from_line("1.3434.22143.321").unwrap();
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+
+        for json in ["{}", "[[1, 2], [2, 1]]", "[[5, null, null, null], [], [], []]"] {
+            assert!(serde_json::from_str::<Board>(json).is_err(), "{}", json);
+        }
+
+        let conflicting = concat!(
+            r#"[[1, ".", ".", "."], [".", 1, ".", "."], "#,
+            r#"[3, 4, ".", "."], [4, 3, ".", "."]]"#
+        );
+        assert!(serde_json::from_str::<Board>(conflicting).is_err());
+        let value = serde_json::from_str(conflicting).unwrap();
+        assert!(Board::from_json(&value).is_err());
+        assert!(Board::from_json_unchecked(&value).is_ok());
+    }
+
+    #[test]
+    fn test_board_peers() {
+        let board = Board::new(3);
+        let peers = board.peers(&Loc::new(5, 5));
+        assert_eq!(peers.len(), 20);
+        assert!(peers.contains(&Loc::new(4, 6)));
+        assert!(peers.contains(&Loc::new(5, 1)));
+        assert!(peers.contains(&Loc::new(9, 5)));
+        assert!(!peers.contains(&Loc::new(5, 5)));
+        assert!(!peers.contains(&Loc::new(4, 7)));
+    }
+
+    #[test]
+    fn test_board_conflicts() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        assert!(board.conflicts().is_empty());
+        board.set(&Loc::new(1, 2), Some(1)).unwrap();
+        assert_eq!(board.conflicts(), vec![Loc::new(1, 1), Loc::new(1, 2), Loc::new(3, 2)]);
+    }
 }
@@ -0,0 +1,175 @@
+use super::prelude::*;
+use super::symbols::SymbolSet;
+use super::{Board, Origin};
+
+/// Characters standing for an empty cell, unless the alphabet uses them.
+const BLANKS: [char; 4] = ['.', '0', '_', '*'];
+
+/// Characters separating groups of cells, skipped while reading.
+const SEPARATORS: [char; 6] = ['|', '+', '-', ',', ';', ':'];
+
+impl Board {
+    /// Reads a board written as a single line of cells, with `.` or `0` for
+    /// blanks, using the compact alphabet for its size.
+    ///
+    /// Whitespace and common separators (`|`, `+`, `-`, `,`, `;`, `:`) are
+    /// ignored, so grids split into several lines are accepted as well.
+    pub fn from_line(line: &str) -> Result<Self, String> {
+        let count = line.chars().filter(|c| !Board::is_separator(*c)).count();
+        let top =
+            Board::calc_size(count).ok_or(format!("{} cells do not form a square board", count))?;
+        Board::from_line_with(line, &SymbolSet::compact(top)?)
+    }
+
+    /// Reads a board written as a single line of cells with a given alphabet
+    /// of single characters. Separators that are symbols of the alphabet are
+    /// read as cells.
+    pub fn from_line_with(line: &str, symbols: &SymbolSet) -> Result<Self, String> {
+        if !symbols.is_compact() {
+            return Err("Alphabet has symbols longer than one character".to_string());
+        }
+        let is_symbol = |c: char| symbols.value(c.encode_utf8(&mut [0; 4])).is_some();
+        let cells: Vec<char> = line
+            .chars()
+            .filter(|c| !Board::is_separator(*c) || is_symbol(*c))
+            .collect();
+        let size = Board::calc_size(cells.len())
+            .and_then(|top| Board::calc_size(top.into()))
+            .ok_or(format!("{} cells do not form a square board", cells.len()))?;
+
+        let mut board = Board::new(size);
+        board.set_symbols(symbols.clone())?;
+        let top = board.top;
+
+        for (loc, cell) in Board::gen_all_locs(top).into_iter().zip(cells) {
+            let mut buf = [0; 4];
+            let value = match symbols.value(cell.encode_utf8(&mut buf)) {
+                Some(v) => Some(v),
+                None if BLANKS.contains(&cell) => None,
+                None => return Err(format!("Unknown symbol '{}' in {}", cell, loc)),
+            };
+            board.force_set(&loc, value, Origin::Given);
+        }
+        Ok(board)
+    }
+
+    /// Writes the board as a single line of cells with `.` for blanks. Boards
+    /// using symbols longer than one character fall back to the compact alphabet,
+    /// which fails for boards above 61x61.
+    pub fn to_line(&self) -> Result<String, String> {
+        let compact;
+        let symbols = if self.symbols.is_compact() {
+            &self.symbols
+        } else {
+            compact = SymbolSet::compact(self.top)?;
+            &compact
+        };
+        Ok(Board::gen_all_locs(self.top)
+            .iter()
+            .map(|loc| match self.value(loc) {
+                Some(v) => symbols.symbol(v).unwrap_or("?"),
+                None => ".",
+            })
+            .collect())
+    }
+
+    fn is_separator(c: char) -> bool {
+        c.is_whitespace() || SEPARATORS.contains(&c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc::Loc;
+
+    const PUZZLE: &str =
+        "...26.7.168..7..9.19...45..82.1...4...46.29...5...3.28..93...74.4..5..367.3.18...";
+    const SOLUTION: &str =
+        "435269781682571493197834562826195347374682915951743628519326874248957136763418259";
+
+    #[test]
+    fn test_board_from_line() {
+        let board = Board::from_line(PUZZLE).unwrap();
+        assert_eq!(
+            board.to_vec()[0],
+            [None, None, None, Some(2), Some(6), None, Some(7), None, Some(1)]
+        );
+        assert_eq!(board.value(&Loc::new(9, 1)), Some(7));
+        assert_eq!(board.values().filter(|v| v.is_some()).count(), 36);
+        assert_eq!(
+            board.solve().map(|b| b.to_vec()),
+            Some(Board::from_line(SOLUTION).unwrap().to_vec())
+        );
+        assert_eq!(Board::from_line(&PUZZLE.replace('.', "0")), Ok(board));
+    }
+
+    #[test]
+    fn test_board_from_line_separators() {
+        let grid = "
+            1 2 | . 4
+            3 4 | 1 2
+            ----+----
+            2 1 | 4 3
+            4 3 | 2 _
+        ";
+        let board = Board::from_line(grid).unwrap();
+        assert_eq!(board.to_line().unwrap(), "12.434122143432.");
+        assert_eq!(board.size(), 2);
+
+        assert_eq!(Board::from_line("1").map(|b| b.to_vec()), Ok(vec![vec![Some(1)]]));
+        assert_eq!(Board::from_line(" * ").map(|b| b.to_vec()), Ok(vec![vec![None]]));
+    }
+
+    #[test]
+    fn test_board_from_line_large() {
+        let mut line = ".".repeat(256);
+        line.replace_range(0..1, "G");
+        line.replace_range(255..256, "a");
+        let board = Board::from_line(&line).unwrap();
+        assert_eq!(board.size(), 4);
+        assert_eq!(board.value(&Loc::new(1, 1)), Some(16));
+        assert_eq!(board.value(&Loc::new(16, 16)), Some(10));
+        assert_eq!(board.to_line().unwrap(), line.replace('a', "A"));
+
+        let hex = SymbolSet::hex(16).unwrap();
+        let board = Board::from_line_with(&line.replace('G', "0"), &hex).unwrap();
+        assert_eq!(board.value(&Loc::new(1, 1)), Some(1));
+        assert_eq!(board.value(&Loc::new(16, 16)), Some(11));
+
+        let board = Board::from_line(&"P".repeat(625)).unwrap();
+        assert_eq!(board.size(), 5);
+        assert_eq!(board.value(&Loc::new(25, 25)), Some(25));
+    }
+
+    #[test]
+    fn test_board_to_line_wide_symbols() {
+        let mut board = Board::new(4);
+        board.set(&Loc::new(1, 1), Some(12)).unwrap();
+        assert!(!board.symbols().is_compact());
+        assert!(board.to_line().unwrap().starts_with("C..."));
+        assert!(Board::new(8).to_line().is_err());
+    }
+
+    #[test]
+    fn test_board_from_line_errors() {
+        assert!(Board::from_line("").is_err());
+        assert!(Board::from_line("12").is_err());
+        assert!(Board::from_line(&".".repeat(80)).is_err());
+        assert!(Board::from_line(&format!("{}x", ".".repeat(80))).is_err());
+        assert!(Board::from_line(&format!("{}5", ".".repeat(15))).is_err());
+        assert!(Board::from_line_with(&".".repeat(16), &SymbolSet::numeric(16)).is_err());
+    }
+
+    #[test]
+    fn test_board_from_line_separator_symbols() {
+        let chars = ["+", "-", ":", "x"].map(String::from).to_vec();
+        let symbols = SymbolSet::custom(chars).unwrap();
+        let board = Board::from_line_with("+-:x :x+- -+x: x:-+", &symbols).unwrap();
+        assert_eq!(board.value(&Loc::new(1, 1)), Some(1));
+        assert_eq!(board.value(&Loc::new(1, 2)), Some(2));
+        assert_eq!(board.value(&Loc::new(2, 1)), Some(3));
+        assert_eq!(board.to_line().unwrap(), "+-:x:x+--+x:x:-+");
+        assert!(board.is_solved());
+    }
+}
@@ -1,9 +1,16 @@
-pub type L = u8;
+use core::fmt;
+use core::str::FromStr;
+
+use super::prelude::*;
+
+pub type L = u16;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "LocData"))]
 pub struct Loc{
-    pub row: u8,
-    pub col: u8
+    pub row: L,
+    pub col: L
 }
 
 impl Loc {
@@ -11,23 +18,66 @@ impl Loc {
         Self{row, col}
     }
 
-    pub fn sqr(&self, size: u8) -> u8 {
+    pub fn sqr(&self, size: L) -> L {
         ((self.row - 1).div_euclid(size) * size) + (self.col - 1).div_euclid(size) + 1
     }
 }
 
-impl From<(u8, u8)> for Loc {
-    fn from(other: (u8, u8)) -> Self {
+/// Serialized form of `Loc`, checked to start at `r1c1`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct LocData {
+    row: L,
+    col: L,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<LocData> for Loc {
+    type Error = String;
+
+    fn try_from(other: LocData) -> Result<Self, Self::Error> {
+        let loc = Loc::new(other.row, other.col);
+        if loc.row == 0 || loc.col == 0 {
+            return Err(format!("Invalid cell {}, rows and columns start at 1", loc));
+        }
+        Ok(loc)
+    }
+}
+
+impl From<(L, L)> for Loc {
+    fn from(other: (L, L)) -> Self {
         Self::new(other.0, other.1)
     }
 }
 
-impl From<Loc> for (u8, u8) {
-    fn from(other: Loc) -> (u8, u8) {
+impl From<Loc> for (L, L) {
+    fn from(other: Loc) -> (L, L) {
         (other.row, other.col)
     }
 }
 
+/// Writes cell names in the `r3c5` notation.
+impl fmt::Display for Loc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "r{}c{}", self.row, self.col)
+    }
+}
+
+/// Parses cell names in the `r3c5` notation, ignoring case.
+impl FromStr for Loc {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        lower
+            .strip_prefix('r')
+            .and_then(|rest| rest.split_once('c'))
+            .and_then(|(row, col)| Some(Loc::new(row.parse().ok()?, col.parse().ok()?)))
+            .filter(|loc| loc.row > 0 && loc.col > 0)
+            .ok_or(format!("Invalid cell '{}', expected e.g. r3c5", s))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,7 +96,33 @@ mod tests {
 
     #[test]
     fn test_loc_into_tuple() {
-        assert_eq!(<(u8, u8)>::from(Loc::new(1, 1)), (1,1));
+        assert_eq!(<(L, L)>::from(Loc::new(1, 1)), (1,1));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_loc_serde() {
+        let loc = Loc::new(2, 3);
+        let json = serde_json::to_string(&loc).unwrap();
+        assert_eq!(json, r#"{"row":2,"col":3}"#);
+        assert_eq!(serde_json::from_str::<Loc>(&json).unwrap(), loc);
+        assert!(serde_json::from_str::<Loc>(r#"{"row":0,"col":3}"#).is_err());
+        assert!(serde_json::from_str::<Loc>(r#"{"row":2,"col":0}"#).is_err());
+    }
+
+    #[test]
+    fn test_loc_display() {
+        assert_eq!(Loc::new(3, 12).to_string(), "r3c12");
+        assert_eq!(Loc::new(3, 12).to_string().parse(), Ok(Loc::new(3, 12)));
+    }
+
+    #[test]
+    fn test_loc_from_str() {
+        assert_eq!("r3c5".parse(), Ok(Loc::new(3, 5)));
+        assert_eq!("R12C1".parse(), Ok(Loc::new(12, 1)));
+        for s in ["", "r3", "c5", "r0c1", "3c5", "r3c5x", "r-1c2"] {
+            assert!(s.parse::<Loc>().is_err(), "{}", s);
+        }
     }
 
     #[test]
@@ -0,0 +1,495 @@
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeMap, Serializer};
+#[cfg(feature = "json")]
+use serde_json::Value;
+
+use super::loc::{Loc, L};
+use super::prelude::*;
+use super::{Board, Origin};
+
+/// Puzzle made of several overlapping grids, such as Samurai or Butterfly.
+///
+/// Cells are addressed with global coordinates starting at `(1, 1)` in the top
+/// left corner of the bounding rectangle. Every grid is placed by the global
+/// location of its top left cell, aligned to box boundaries, so shared boxes
+/// have to satisfy the rules of all grids they belong to.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultiGrid {
+    size: L,
+    height: L,
+    width: L,
+    origins: Vec<Loc>,
+    grids: Vec<Board>,
+}
+
+impl MultiGrid {
+    pub fn new(size: L, origins: Vec<Loc>) -> Result<Self, String> {
+        if size == 0 {
+            return Err("Grid size must be positive".to_string());
+        }
+        if origins.is_empty() {
+            return Err("At least one grid is required".to_string());
+        }
+        let top = Board::calc_top(size).ok_or(format!("Grid size {} is too large", size))?;
+        let (mut height, mut width) = (0, 0);
+        for origin in origins.iter() {
+            if origin.row == 0 || origin.col == 0 {
+                return Err(format!("Grid origin {} is out of range", origin));
+            }
+            if (origin.row - 1) % size != 0 || (origin.col - 1) % size != 0 {
+                return Err(format!(
+                    "Grid origin {} is not aligned to boxes of size {}",
+                    origin, size
+                ));
+            }
+            let bottom = origin.row.checked_add(top - 1);
+            let right = origin.col.checked_add(top - 1);
+            match (bottom, right) {
+                (Some(b), Some(r)) => {
+                    height = height.max(b);
+                    width = width.max(r);
+                }
+                _ => return Err(format!("Grid origin {} is out of range", origin)),
+            }
+        }
+        let grids = origins.iter().map(|_| Board::new(size)).collect();
+        Ok(Self {
+            size,
+            height,
+            width,
+            origins,
+            grids,
+        })
+    }
+
+    fn preset(origins: &[(L, L)]) -> Self {
+        Self::new(3, origins.iter().map(|o| Loc::from(*o)).collect()).unwrap()
+    }
+
+    /// Two grids sharing a single box in the middle.
+    pub fn twin() -> Self {
+        Self::preset(&[(1, 1), (7, 7)])
+    }
+
+    /// Four grids overlapping in a 12x12 square.
+    pub fn butterfly() -> Self {
+        Self::preset(&[(1, 1), (1, 4), (4, 1), (4, 4)])
+    }
+
+    /// Central grid with four grids shifted by a box in every direction.
+    pub fn flower() -> Self {
+        Self::preset(&[(1, 4), (4, 1), (4, 4), (4, 7), (7, 4)])
+    }
+
+    /// Four corner grids sharing a box each with the central one.
+    pub fn samurai() -> Self {
+        Self::preset(&[(1, 1), (1, 13), (7, 7), (13, 1), (13, 13)])
+    }
+
+    pub fn size(&self) -> L {
+        self.size
+    }
+
+    pub fn height(&self) -> L {
+        self.height
+    }
+
+    pub fn width(&self) -> L {
+        self.width
+    }
+
+    pub fn origins(&self) -> &[Loc] {
+        &self.origins
+    }
+
+    pub fn grids(&self) -> &[Board] {
+        &self.grids
+    }
+
+    /// Lists grids containing the global `loc` along with its local location.
+    pub fn grids_at(&self, loc: &Loc) -> Vec<(usize, Loc)> {
+        let top = self.grids[0].top();
+        self.origins
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| {
+                loc.row >= o.row
+                    && loc.col >= o.col
+                    && loc.row - o.row < top
+                    && loc.col - o.col < top
+            })
+            .map(|(i, o)| (i, Loc::new(loc.row - o.row + 1, loc.col - o.col + 1)))
+            .collect()
+    }
+
+    pub fn contains(&self, loc: &Loc) -> bool {
+        !self.grids_at(loc).is_empty()
+    }
+
+    /// Lists global locations of all cells belonging to at least one grid.
+    pub fn locs(&self) -> Vec<Loc> {
+        Board::gen_all_locs(self.height.max(self.width))
+            .into_iter()
+            .filter(|l| l.row <= self.height && l.col <= self.width && self.contains(l))
+            .collect()
+    }
+
+    pub fn value(&self, loc: &Loc) -> Option<L> {
+        self.grids_at(loc)
+            .first()
+            .and_then(|(i, local)| self.grids[*i].value(local))
+    }
+
+    /// Places a digit entered by the player in every grid sharing the cell.
+    /// Givens and cells outside of every grid cannot be changed.
+    pub fn set(&mut self, loc: &Loc, value: Option<L>) -> Result<(), String> {
+        let grids = self.grids_at(loc);
+        if grids.is_empty() {
+            return Err(format!("{} does not belong to any grid", loc));
+        }
+        for (i, local) in grids.iter() {
+            if self.grids[*i].origin(local) == Some(Origin::Given) {
+                return Err(format!("Cannot change the given in {}", loc));
+            }
+        }
+        for (i, local) in grids {
+            self.grids[i].force_set(&local, value, Origin::Entered);
+        }
+        Ok(())
+    }
+
+    /// Sets a cell in every grid sharing it, regardless of its current origin.
+    pub fn force_set(&mut self, loc: &Loc, value: Option<L>, origin: Origin) {
+        for (i, local) in self.grids_at(loc) {
+            self.grids[i].force_set(&local, value, origin);
+        }
+    }
+
+    pub fn available_values(&self, loc: &Loc) -> Vec<L> {
+        let mut grids = self.grids_at(loc).into_iter();
+        let mut values = match grids.next() {
+            Some((i, local)) => self.grids[i].available_values(&local),
+            None => return Vec::new(),
+        };
+        for (i, local) in grids {
+            let other = self.grids[i].available_values(&local);
+            values.retain(|v| other.contains(v));
+        }
+        values
+    }
+
+    pub fn try_set(&mut self, loc: &Loc, value: Option<L>) -> Result<(), String> {
+        if !self.contains(loc) {
+            return Err(format!("{} does not belong to any grid", loc));
+        }
+        if let Some(v) = value {
+            if !self.available_values(loc).contains(&v) {
+                return Err(format!("'{}' cannot be inserted in {}", v, loc));
+            }
+        }
+        self.set(loc, value)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.grids.iter().all(|g| g.is_solved())
+    }
+
+    fn try_to_solve(&mut self, missing: &mut Vec<Loc>) -> bool {
+        let best = missing
+            .iter()
+            .enumerate()
+            .map(|(i, loc)| (i, self.available_values(loc)))
+            .min_by_key(|(_, values)| values.len());
+
+        let (index, values) = match best {
+            Some(b) => b,
+            None => return self.is_solved(),
+        };
+
+        let loc = missing.swap_remove(index);
+        for value in values {
+            self.force_set(&loc, Some(value), Origin::Solved);
+            if self.try_to_solve(missing) {
+                return true;
+            }
+        }
+        self.force_set(&loc, None, Origin::Solved);
+        missing.push(loc);
+        let last = missing.len() - 1;
+        missing.swap(index, last);
+        false
+    }
+
+    /// Solves all grids at once, always branching on the cell with the fewest candidates.
+    pub fn solve(&self) -> Option<Self> {
+        let mut result = self.clone();
+        let mut missing: Vec<Loc> = self
+            .locs()
+            .into_iter()
+            .filter(|l| self.value(l).is_none())
+            .collect();
+
+        if result.try_to_solve(&mut missing) {
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<Vec<Option<L>>> {
+        (1..=self.height)
+            .map(|row| {
+                (1..=self.width)
+                    .map(|col| self.value(&Loc::new(row, col)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Renders all grids on a shared canvas, leaving gaps where no grid is placed.
+    pub fn draw(&self) -> String {
+        let size = usize::from(self.size);
+        let cell = self.grids[0].top().to_string().len();
+        let (box_rows, box_cols) = (
+            usize::from(self.height) / size,
+            usize::from(self.width) / size,
+        );
+        let (box_height, box_width) = (size + 1, size * (cell + 1) + 2);
+        let mut canvas = vec![vec![' '; box_cols * box_width + 1]; box_rows * box_height + 1];
+
+        for br in 0..box_rows {
+            for bc in 0..box_cols {
+                let corner = Loc::new((br * size + 1) as L, (bc * size + 1) as L);
+                if !self.contains(&corner) {
+                    continue;
+                }
+                let (y, x) = (br * box_height, bc * box_width);
+                for dx in 0..=box_width {
+                    let c = if dx % box_width == 0 { '+' } else { '-' };
+                    canvas[y][x + dx] = c;
+                    canvas[y + box_height][x + dx] = c;
+                }
+                for dy in 1..box_height {
+                    canvas[y + dy][x] = '|';
+                    canvas[y + dy][x + box_width] = '|';
+                    let row = corner.row + (dy - 1) as L;
+                    for dc in 0..size {
+                        let loc = Loc::new(row, corner.col + dc as L);
+                        let text = match self.value(&loc) {
+                            Some(v) => format!("{:>width$}", v, width = cell),
+                            None => format!("{:>width$}", ".", width = cell),
+                        };
+                        let start = x + 2 + dc * (cell + 1);
+                        for (i, ch) in text.chars().enumerate() {
+                            canvas[y + dy][start + i] = ch;
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut output = String::new();
+        for line in canvas {
+            output.push_str(line.into_iter().collect::<String>().trim_end());
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MultiGrid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let origins: Vec<(L, L)> = self.origins.iter().map(|o| (*o).into()).collect();
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("size", &self.size)?;
+        map.serialize_entry("grids", &origins)?;
+        map.serialize_entry("values", &self.to_vec())?;
+        map.end()
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<&MultiGrid> for Value {
+    fn from(other: &MultiGrid) -> Value {
+        serde_json::json!(other)
+    }
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<Value> for MultiGrid {
+    type Error = String;
+
+    fn try_from(other: Value) -> Result<Self, Self::Error> {
+        let size = other["size"]
+            .as_u64()
+            .and_then(|s| L::try_from(s).ok())
+            .ok_or("Missing or invalid 'size'")?;
+        let origins = other["grids"]
+            .as_array()
+            .ok_or("Missing or invalid 'grids'")?
+            .iter()
+            .map(|o| match (o[0].as_u64(), o[1].as_u64()) {
+                (Some(row), Some(col)) => Ok(Loc::new(
+                    L::try_from(row).map_err(|e| e.to_string())?,
+                    L::try_from(col).map_err(|e| e.to_string())?,
+                )),
+                _ => Err(format!("Invalid grid origin {}", o)),
+            })
+            .collect::<Result<Vec<Loc>, String>>()?;
+        let mut result = Self::new(size, origins)?;
+
+        if let Some(rows) = other.get("values") {
+            let rows = rows.as_array().ok_or("Invalid 'values'")?;
+            for (row, cols) in rows.iter().enumerate() {
+                let cols = cols.as_array().ok_or(format!("Invalid row {}", row + 1))?;
+                for (col, value) in cols.iter().enumerate() {
+                    if value.is_null() {
+                        continue;
+                    }
+                    let loc = Loc::new((row + 1) as L, (col + 1) as L);
+                    let value = value
+                        .as_u64()
+                        .and_then(|v| L::try_from(v).ok())
+                        .filter(|v| (1..=result.grids[0].top()).contains(v))
+                        .ok_or(format!("Invalid value {} in {}", value, loc))?;
+                    if !result.available_values(&loc).contains(&value) {
+                        return Err(format!("'{}' cannot be inserted in {}", value, loc));
+                    }
+                    result.force_set(&loc, Some(value), Origin::Given);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<&str> for MultiGrid {
+    type Error = String;
+
+    fn try_from(other: &str) -> Result<Self, Self::Error> {
+        Self::try_from(serde_json::from_str::<Value>(other).map_err(|e| e.to_string())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multigrid_new() {
+        assert!(MultiGrid::new(0, vec![Loc::new(1, 1)]).is_err());
+        assert!(MultiGrid::new(3, vec![]).is_err());
+        assert!(MultiGrid::new(3, vec![Loc::new(1, 2)]).is_err());
+        assert!(MultiGrid::new(3, vec![Loc::new(L::MAX - 2, 1)]).is_err());
+        assert!(MultiGrid::new(256, vec![Loc::new(1, 1)]).is_err());
+
+        let grid = MultiGrid::new(2, vec![Loc::new(1, 1), Loc::new(3, 3)]).unwrap();
+        assert_eq!((grid.height(), grid.width()), (6, 6));
+        assert_eq!(grid.locs().len(), 28);
+    }
+
+    #[test]
+    fn test_multigrid_presets() {
+        let tests = [
+            (MultiGrid::twin(), 15, 15, 153),
+            (MultiGrid::butterfly(), 12, 12, 144),
+            (MultiGrid::flower(), 15, 15, 189),
+            (MultiGrid::samurai(), 21, 21, 369),
+        ];
+        for (grid, height, width, cells) in tests {
+            assert_eq!((grid.height(), grid.width()), (height, width));
+            assert_eq!(grid.locs().len(), cells);
+        }
+    }
+
+    #[test]
+    fn test_multigrid_grids_at() {
+        let grid = MultiGrid::samurai();
+        assert_eq!(grid.grids_at(&Loc::new(1, 1)), vec![(0, Loc::new(1, 1))]);
+        assert_eq!(
+            grid.grids_at(&Loc::new(8, 8)),
+            vec![(0, Loc::new(8, 8)), (2, Loc::new(2, 2))]
+        );
+        assert!(grid.grids_at(&Loc::new(1, 10)).is_empty());
+    }
+
+    #[test]
+    fn test_multigrid_shared_values() {
+        let mut grid = MultiGrid::twin();
+        grid.set(&Loc::new(9, 9), Some(5)).unwrap();
+        assert_eq!(grid.grids()[0].value(&Loc::new(9, 9)), Some(5));
+        assert_eq!(grid.grids()[1].value(&Loc::new(3, 3)), Some(5));
+
+        assert!(!grid.available_values(&Loc::new(9, 1)).contains(&5));
+        assert!(!grid.available_values(&Loc::new(15, 9)).contains(&5));
+        assert!(grid.available_values(&Loc::new(15, 1)).is_empty());
+        assert!(grid.try_set(&Loc::new(9, 15), Some(5)).is_err());
+        assert!(grid.try_set(&Loc::new(1, 15), Some(5)).is_err());
+        assert_eq!(
+            grid.set(&Loc::new(1, 15), Some(5)),
+            Err("r1c15 does not belong to any grid".to_string())
+        );
+    }
+
+    #[test]
+    fn test_multigrid_solve() {
+        for grid in [
+            MultiGrid::new(2, vec![Loc::new(1, 1), Loc::new(3, 3)]).unwrap(),
+            MultiGrid::twin(),
+            MultiGrid::samurai(),
+        ] {
+            let solved = grid.solve().expect("Empty grid should be solvable");
+            assert!(solved.is_solved());
+            assert!(solved.locs().iter().all(|l| solved.value(l).is_some()));
+        }
+    }
+
+    #[test]
+    fn test_multigrid_solve_unsolvable() {
+        let mut grid = MultiGrid::new(2, vec![Loc::new(1, 1), Loc::new(3, 3)]).unwrap();
+        grid.set(&Loc::new(3, 1), Some(1)).unwrap();
+        grid.set(&Loc::new(3, 2), Some(2)).unwrap();
+        grid.set(&Loc::new(5, 3), Some(3)).unwrap();
+        grid.set(&Loc::new(6, 3), Some(4)).unwrap();
+        assert!(grid.available_values(&Loc::new(3, 3)).is_empty());
+        assert_eq!(grid.solve(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_multigrid_json() {
+        let mut grid = MultiGrid::new(1, vec![Loc::new(1, 1), Loc::new(2, 2)]).unwrap();
+        grid.force_set(&Loc::new(2, 2), Some(1), Origin::Given);
+        let json = Value::from(&grid);
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "size": 1,
+                "grids": [[1, 1], [2, 2]],
+                "values": [[null, null], [null, 1]],
+            })
+        );
+        assert_eq!(MultiGrid::try_from(json), Ok(grid));
+
+        assert!(MultiGrid::try_from("{\"size\": 1}").is_err());
+        assert!(
+            MultiGrid::try_from("{\"size\": 2, \"grids\": [[1, 1]], \"values\": [[1, 1]]}")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_multigrid_draw() {
+        let mut grid = MultiGrid::new(1, vec![Loc::new(1, 1), Loc::new(2, 2)]).unwrap();
+        grid.set(&Loc::new(1, 1), Some(1)).unwrap();
+        assert_eq!(
+            grid.draw(),
+            "+---+\n| 1 |\n+---+---+\n    | . |\n    +---+\n"
+        );
+    }
+}
@@ -0,0 +1,429 @@
+use core::fmt::Write;
+
+use super::Board;
+use super::loc::Loc;
+use super::prelude::*;
+
+/// Width and height of an A4 page in points.
+const PAGE: (f32, f32) = (595.0, 842.0);
+
+/// Space left blank around the page contents, in points.
+const MARGIN: f32 = 50.0;
+
+/// Average width of a Helvetica glyph relative to the font size, used to
+/// centre text without font metrics.
+const GLYPH: f32 = 0.556;
+
+/// Characters of Windows-1252 between 0x80 and 0x9F, the rest of the range
+/// above ASCII matching Latin-1.
+const CP1252: [(char, u8); 27] = [
+    ('€', 0x80),
+    ('‚', 0x82),
+    ('ƒ', 0x83),
+    ('„', 0x84),
+    ('…', 0x85),
+    ('†', 0x86),
+    ('‡', 0x87),
+    ('ˆ', 0x88),
+    ('‰', 0x89),
+    ('Š', 0x8a),
+    ('‹', 0x8b),
+    ('Œ', 0x8c),
+    ('Ž', 0x8e),
+    ('‘', 0x91),
+    ('’', 0x92),
+    ('“', 0x93),
+    ('”', 0x94),
+    ('•', 0x95),
+    ('–', 0x96),
+    ('—', 0x97),
+    ('˜', 0x98),
+    ('™', 0x99),
+    ('š', 0x9a),
+    ('›', 0x9b),
+    ('œ', 0x9c),
+    ('ž', 0x9e),
+    ('Ÿ', 0x9f),
+];
+
+/// Print-ready booklet of puzzles with an answer key at the back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Booklet {
+    /// Title printed at the top of every page.
+    pub title: String,
+    /// Puzzles laid out on a single page.
+    pub per_page: usize,
+    /// Solutions laid out on a single page of the answer key.
+    pub answers_per_page: usize,
+    /// Whether the answer key is added after the puzzles.
+    pub answers: bool,
+    /// Puzzles along with their difficulty labels.
+    pub puzzles: Vec<(Board, Option<String>)>,
+}
+
+impl Booklet {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            per_page: 4,
+            answers_per_page: 9,
+            answers: true,
+            puzzles: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, board: Board, label: Option<&str>) {
+        self.puzzles.push((board, label.map(String::from)));
+    }
+
+    /// Lays out the booklet and writes it as a PDF document.
+    ///
+    /// Fails for an empty booklet or if a puzzle of the answer key cannot be
+    /// solved. Text is printed with the standard Helvetica font, so titles,
+    /// labels and symbols have to fit in Windows-1252.
+    pub fn to_pdf(&self) -> Result<Vec<u8>, String> {
+        if self.per_page == 0 || self.answers_per_page == 0 {
+            return Err("Pages have to hold at least one puzzle".to_string());
+        }
+        if self.puzzles.is_empty() {
+            return Err("Booklet has no puzzles".to_string());
+        }
+        let mut pages = Vec::new();
+        for (i, chunk) in self.puzzles.chunks(self.per_page).enumerate() {
+            let slots: Vec<(String, &Board)> = chunk
+                .iter()
+                .enumerate()
+                .map(|(j, (board, label))| {
+                    let number = i * self.per_page + j + 1;
+                    let caption = match label {
+                        Some(l) => format!("#{} - {}", number, l),
+                        None => format!("#{}", number),
+                    };
+                    (caption, board)
+                })
+                .collect();
+            pages.push(self.layout(&self.title, &slots, self.per_page, 14.0)?);
+        }
+
+        if self.answers {
+            let solutions = self
+                .puzzles
+                .iter()
+                .enumerate()
+                .map(|(i, (board, _))| {
+                    board
+                        .solve()
+                        .ok_or(format!("Puzzle #{} has no solution", i + 1))
+                })
+                .collect::<Result<Vec<Board>, String>>()?;
+            let heading = format!("{} - Answers", self.title);
+            for (i, chunk) in solutions.chunks(self.answers_per_page).enumerate() {
+                let slots: Vec<(String, &Board)> = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(j, board)| (format!("#{}", i * self.answers_per_page + j + 1), board))
+                    .collect();
+                pages.push(self.layout(&heading, &slots, self.answers_per_page, 10.0)?);
+            }
+        }
+
+        for (i, content) in pages.iter_mut().enumerate() {
+            let number = (i + 1).to_string();
+            text(
+                content,
+                "F1",
+                10.0,
+                PAGE.0 / 2.0,
+                MARGIN / 2.0,
+                &number,
+                true,
+            )?;
+        }
+        Ok(write_document(&pages))
+    }
+
+    /// Draws a page of boards arranged in a grid of slots, under a heading.
+    fn layout(
+        &self,
+        heading: &str,
+        slots: &[(String, &Board)],
+        per_page: usize,
+        caption: f32,
+    ) -> Result<String, String> {
+        let mut content = String::new();
+        text(
+            &mut content,
+            "F2",
+            18.0,
+            PAGE.0 / 2.0,
+            PAGE.1 - MARGIN,
+            heading,
+            true,
+        )?;
+
+        let cols = (1..).find(|c| c * c >= per_page).unwrap_or(1);
+        let rows = per_page.div_ceil(cols);
+        let width = (PAGE.0 - 2.0 * MARGIN) / cols as f32;
+        let height = (PAGE.1 - 3.0 * MARGIN) / rows as f32;
+        let side = width.min(height - 2.0 * caption) * 0.9;
+
+        for (i, (label, board)) in slots.iter().enumerate() {
+            let (row, col) = ((i / cols) as f32, (i % cols) as f32);
+            let x = MARGIN + col * width + (width - side) / 2.0;
+            let y = PAGE.1 - 2.0 * MARGIN - row * height;
+            text(&mut content, "F1", caption, x, y - caption, label, false)?;
+            draw_board(&mut content, board, x, y - 1.5 * caption, side)?;
+        }
+        Ok(content)
+    }
+}
+
+/// Byte of a character above ASCII in Windows-1252, the encoding of the
+/// standard fonts.
+fn cp1252(c: char) -> Option<u8> {
+    match c {
+        '\u{a0}'..='\u{ff}' => u8::try_from(c).ok(),
+        _ => CP1252.iter().find(|(k, _)| *k == c).map(|(_, b)| *b),
+    }
+}
+
+/// Escapes characters with a special meaning in PDF strings, and writes
+/// characters above ASCII as octal escapes of their Windows-1252 bytes.
+fn escape(text: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                output.push('\\');
+                output.push(c);
+            }
+            ' '..='~' => output.push(c),
+            _ => {
+                let byte =
+                    cp1252(c).ok_or(format!("Cannot print '{}' with the standard fonts", c))?;
+                let _ = write!(output, "\\{:03o}", byte);
+            }
+        }
+    }
+    Ok(output)
+}
+
+fn text(
+    content: &mut String,
+    font: &str,
+    size: f32,
+    x: f32,
+    y: f32,
+    value: &str,
+    centered: bool,
+) -> Result<(), String> {
+    let x = match centered {
+        true => x - value.chars().count() as f32 * size * GLYPH / 2.0,
+        false => x,
+    };
+    let _ = writeln!(
+        content,
+        "BT /{} {:.1} Tf {:.2} {:.2} Td ({}) Tj ET",
+        font,
+        size,
+        x,
+        y,
+        escape(value)?
+    );
+    Ok(())
+}
+
+/// Draws a board with its top left corner at `x` and `y`.
+fn draw_board(
+    content: &mut String,
+    board: &Board,
+    x: f32,
+    y: f32,
+    side: f32,
+) -> Result<(), String> {
+    let top = board.top();
+    let cell = side / f32::from(top);
+    let size = cell * 0.6;
+
+    for loc in Board::gen_all_locs(top) {
+        if board.value(&loc).is_some() {
+            let Loc { row, col } = loc;
+            let cx = x + (f32::from(col) - 0.5) * cell;
+            let cy = y - (f32::from(row) - 0.5) * cell - size * 0.35;
+            text(content, "F1", size, cx, cy, &board.get_str(&loc), true)?;
+        }
+    }
+    for i in 0..=top {
+        let offset = f32::from(i) * cell;
+        let width = match i % board.size() {
+            0 => 2.0,
+            _ => 0.5,
+        };
+        let _ = writeln!(
+            content,
+            "{:.1} w {:.2} {:.2} m {:.2} {:.2} l S {:.2} {:.2} m {:.2} {:.2} l S",
+            width,
+            x + offset,
+            y,
+            x + offset,
+            y - side,
+            x,
+            y - offset,
+            x + side,
+            y - offset
+        );
+    }
+    Ok(())
+}
+
+/// Writes pages with the given content streams as a PDF document using the
+/// standard Helvetica fonts.
+fn write_document(pages: &[String]) -> Vec<u8> {
+    let first = 5;
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", first + 2 * i + 1))
+        .collect();
+    let font = |name: &str| {
+        format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+            name
+        )
+    };
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+        font("Helvetica"),
+        font("Helvetica-Bold"),
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE.0,
+            PAGE.1,
+            first + 2 * i
+        ));
+    }
+
+    let mut output = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(output.len());
+        let _ = write!(output, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+    let xref = output.len();
+    let _ = write!(
+        output,
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    );
+    for offset in offsets {
+        let _ = writeln!(output, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        output,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+    output.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "...26.7.168..7..9.19...45..82.1...4...46.29...5...3.28..93...74.4..5..367.3.18...";
+
+    fn booklet(count: usize) -> Booklet {
+        let mut booklet = Booklet::new("Daily (Sudoku)");
+        for i in 0..count {
+            let label = if i % 2 == 0 { Some("Easy") } else { None };
+            booklet.push(Board::from_line(PUZZLE).unwrap(), label);
+        }
+        booklet
+    }
+
+    #[test]
+    fn test_booklet_to_pdf() {
+        let pdf = String::from_utf8(booklet(5).to_pdf().unwrap()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/Count 3"));
+        assert_eq!(pdf.matches("/Type /Page ").count(), 3);
+        assert!(pdf.contains("(Daily \\(Sudoku\\))"));
+        assert!(pdf.contains("(#3 - Easy)"));
+        assert!(pdf.contains("(#4)"));
+        assert!(pdf.contains("(Daily \\(Sudoku\\) - Answers)"));
+        assert!(pdf.contains("(3) Tj"));
+
+        let xref = pdf.rfind("\nxref\n").unwrap() + 1;
+        let offsets: Vec<usize> = pdf[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .map(|l| l[..10].parse().unwrap())
+            .collect();
+        assert_eq!(offsets.len(), 4 + 2 * 3);
+        for (i, offset) in offsets.into_iter().enumerate() {
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+
+    #[test]
+    fn test_booklet_layout() {
+        let mut booklet = booklet(5);
+        booklet.per_page = 1;
+        booklet.answers = false;
+        let pdf = String::from_utf8(booklet.to_pdf().unwrap()).unwrap();
+        assert_eq!(pdf.matches("/Type /Page ").count(), 5);
+        assert!(!pdf.contains("Answers"));
+
+        booklet.per_page = 0;
+        assert!(booklet.to_pdf().is_err());
+        assert_eq!(
+            Booklet::new("Empty").to_pdf(),
+            Err("Booklet has no puzzles".to_string())
+        );
+    }
+
+    #[test]
+    fn test_booklet_encoding() {
+        let mut booklet = booklet(1);
+        booklet.title = "Caf\u{e9} \u{2013} 5\u{20ac}".to_string();
+        let pdf = booklet.to_pdf().unwrap();
+        assert!(pdf.is_ascii());
+        let pdf = String::from_utf8(pdf).unwrap();
+        assert!(pdf.contains("(Caf\\351 \\226 5\\200)"));
+
+        booklet.title = "\u{6570}\u{72ec}".to_string();
+        assert_eq!(
+            booklet.to_pdf(),
+            Err("Cannot print '\u{6570}' with the standard fonts".to_string())
+        );
+    }
+
+    #[test]
+    fn test_booklet_unsolvable() {
+        let mut booklet = booklet(1);
+        booklet.push(
+            Board::from_line("11..............").unwrap(),
+            Some("Broken"),
+        );
+        assert_eq!(
+            booklet.to_pdf(),
+            Err("Puzzle #2 has no solution".to_string())
+        );
+        booklet.answers = false;
+        assert!(booklet.to_pdf().is_ok());
+    }
+}
@@ -0,0 +1,216 @@
+use super::loc::{Loc, L};
+use super::prelude::*;
+use super::symbols::SymbolSet;
+use super::{Board, Origin};
+
+/// Characters used for borders of a pencil-mark grid, skipped while reading.
+const BORDERS: [char; 4] = ['|', ':', '*', '\''];
+
+impl Board {
+    /// Reads a pencil-mark grid, where every cell is written as its list of
+    /// candidates and boxes are separated by `|` and `-`.
+    ///
+    /// Cells with a single candidate are read as placed digits, all other cells
+    /// keep their candidates as pencil marks. A lone `.` stands for an empty
+    /// cell without any candidates left.
+    pub fn from_pencil_marks(text: &str) -> Result<Self, String> {
+        let rows: Vec<Vec<&str>> = text
+            .lines()
+            .map(|l| {
+                l.split(|c: char| c.is_whitespace() || BORDERS.contains(&c))
+                    .filter(|t| !t.is_empty() && !Board::is_rule(t))
+                    .collect::<Vec<&str>>()
+            })
+            .filter(|cells| !cells.is_empty())
+            .collect();
+        let size = Board::calc_size(rows.len())
+            .ok_or(format!("{} rows do not form a square board", rows.len()))?;
+        let mut board = Board::new(size);
+        let symbols = SymbolSet::compact(board.top)?;
+        board.set_symbols(symbols.clone())?;
+
+        for (row, cells) in (1..=board.top).zip(rows) {
+            if cells.len() != usize::from(board.top) {
+                return Err(format!("Row {} does not have {} cells", row, board.top));
+            }
+            for (col, cell) in (1..=board.top).zip(cells) {
+                let loc = Loc::new(row, col);
+                let values = match cell {
+                    "." => Vec::new(),
+                    _ => cell
+                        .chars()
+                        .map(|c| {
+                            symbols
+                                .value(c.encode_utf8(&mut [0; 4]))
+                                .ok_or(format!("Unknown symbol '{}' in {}", c, loc))
+                        })
+                        .collect::<Result<Vec<L>, String>>()?,
+                };
+                match values[..] {
+                    [value] => board.force_set(&loc, Some(value), Origin::Given),
+                    _ => board.set_marks(&loc, Some(values)),
+                }
+            }
+        }
+        Ok(board)
+    }
+
+    /// Checks whether a token is a part of a horizontal border, like `---+---`.
+    fn is_rule(token: &str) -> bool {
+        token.len() > 1 && token.chars().all(|c| matches!(c, '-' | '+' | '.' | '='))
+    }
+
+    /// Writes the current candidates of every cell in the pencil-mark layout,
+    /// with placed digits written as single candidates. Fails for boards above
+    /// 61x61, which the compact alphabet cannot hold.
+    pub fn to_pencil_marks(&self) -> Result<String, String> {
+        let symbols = SymbolSet::compact(self.top)?;
+        let (size, top) = (usize::from(self.size), usize::from(self.top));
+        let cells: Vec<Vec<String>> = (1..=self.top)
+            .map(|row| {
+                (1..=self.top)
+                    .map(|col| {
+                        let loc = Loc::new(row, col);
+                        let values = match self.value(&loc) {
+                            Some(v) => vec![v],
+                            None => self.candidates(&loc),
+                        };
+                        match values.is_empty() {
+                            true => ".".to_string(),
+                            false => values
+                                .into_iter()
+                                .map(|v| symbols.symbol(v).unwrap_or("?"))
+                                .collect(),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = (0..top)
+            .map(|col| cells.iter().map(|r| r[col].len()).max().unwrap_or(1))
+            .collect();
+        let segments: Vec<usize> = widths
+            .chunks(size)
+            .map(|w| w.iter().sum::<usize>() + w.len() + 1)
+            .collect();
+
+        let border = format!(
+            "*{}*\n",
+            "-".repeat(segments.iter().sum::<usize>() + size - 1)
+        );
+        let separator = format!(
+            "|{}|\n",
+            segments
+                .iter()
+                .map(|s| "-".repeat(*s))
+                .collect::<Vec<String>>()
+                .join("+")
+        );
+        let mut output = border.clone();
+        for (r, row) in cells.iter().enumerate() {
+            if r > 0 && r % size == 0 {
+                output.push_str(&separator);
+            }
+            let boxes: Vec<String> = row
+                .chunks(size)
+                .zip(widths.chunks(size))
+                .map(|(cells, widths)| {
+                    let padded: Vec<String> = cells
+                        .iter()
+                        .zip(widths)
+                        .map(|(c, w)| format!("{:<w$}", c, w = w))
+                        .collect();
+                    format!(" {} ", padded.join(" "))
+                })
+                .collect();
+            output.push_str(&format!("|{}|\n", boxes.join("|")));
+        }
+        output.push_str(&border);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_from_pencil_marks() {
+        let grid = "
+*-----------------*
+| 13  2   | 13  4 |
+| 3   4   | 12  2 |
+|---------+-------|
+| 2   13  | 4   3 |
+| 4   3   | 2   1 |
+*-----------------*
+";
+        let board = Board::from_pencil_marks(grid).unwrap();
+        assert_eq!(board.to_line().unwrap(), ".2.434.22.434321");
+        assert_eq!(board.marks(&Loc::new(1, 1)), Some(&vec![1, 3]));
+        assert_eq!(board.candidates(&Loc::new(1, 1)), vec![1]);
+        assert_eq!(board.candidates(&Loc::new(2, 3)), vec![1]);
+        assert!(board.candidates(&Loc::new(2, 4)).is_empty());
+
+        let solved = "
+.-------.-------.
+| 1  2  | 3  4  |
+| 3  4  | 1  2  |
+:-------+-------:
+| 2  1  | 4  3  |
+| 4  3  | 2  1  |
+'-------'-------'
+";
+        assert!(Board::from_pencil_marks(solved).unwrap().is_solved());
+    }
+
+    #[test]
+    fn test_board_from_pencil_marks_narrower() {
+        let board = Board::from_pencil_marks(
+            "
+| 1    234  | 234  234  |
+| 234  234  | 234  234  |
+|-----------+-----------|
+| 234  234  | 234  234  |
+| 234  234  | 24   .    |
+",
+        )
+        .unwrap();
+        assert_eq!(board.available_values(&Loc::new(4, 3)), vec![1, 2, 3, 4]);
+        assert_eq!(board.candidates(&Loc::new(4, 3)), vec![2, 4]);
+        assert!(board.candidates(&Loc::new(4, 4)).is_empty());
+        assert_eq!(
+            Board::from_pencil_marks(&board.to_pencil_marks().unwrap()),
+            Ok(board)
+        );
+    }
+
+    #[test]
+    fn test_board_to_pencil_marks() {
+        let mut board = Board::from_line(".2.43..22..34..1").unwrap();
+        board.set_marks(&Loc::new(3, 2), Some(vec![1, 4]));
+        assert_eq!(
+            board.to_pencil_marks().unwrap(),
+            "*-------------*
+| 1 2  | 13 4 |
+| 3 14 | 1  2 |
+|------+------|
+| 2 1  | 4  3 |
+| 4 3  | 2  1 |
+*-------------*
+"
+        );
+        assert!(Board::new(8).to_pencil_marks().is_err());
+    }
+
+    #[test]
+    fn test_board_from_pencil_marks_errors() {
+        assert!(Board::from_pencil_marks("").is_err());
+        assert!(Board::from_pencil_marks("| 1 2 |\n| 2 1 |").is_err());
+        assert!(Board::from_pencil_marks("| 1 2 | 3 4 |\n| 1 |\n| 1 |\n| 1 |").is_err());
+        assert!(
+            Board::from_pencil_marks("| 1 2 | 3 x |\n| 1 2 3 4 |\n| 1 2 3 4 |\n| 1 2 3 4 |")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use super::loc::{Loc, L};
+use super::prelude::*;
+use super::{Board, Origin};
+
+/// Rough difficulty of a puzzle, from the techniques needed to solve it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Difficulty {
+    /// Naked singles are enough.
+    Easy,
+    /// Hidden singles are needed too.
+    Medium,
+    /// A few guesses are needed.
+    Hard,
+    /// Many guesses are needed.
+    Expert,
+}
+
+/// Outcome of `Board::rate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    /// Cells filled before rating.
+    pub givens: usize,
+    /// Cells filled because only one digit fits them.
+    pub naked_singles: usize,
+    /// Cells filled because a digit fits nowhere else in a row, column or box.
+    pub hidden_singles: usize,
+    /// Digits tried in cells with more than one candidate.
+    pub guesses: usize,
+}
+
+/// Work done by the search of `Board::solve_with_stats`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolveStats {
+    /// Digits placed while searching.
+    pub nodes: usize,
+    /// Digits taken back after leading to a dead end.
+    pub backtracks: usize,
+    /// Most cells filled by the search at the same time.
+    pub max_depth: usize,
+    /// Candidate lists computed for empty cells.
+    pub propagations: usize,
+    /// Wall time of the search, always zero without the `std` feature.
+    pub elapsed: Duration,
+}
+
+/// Guesses above which a puzzle is rated `Difficulty::Expert`.
+const HARD_GUESSES: usize = 5;
+
+/// Depth-first search choosing the cell with the fewest candidates first.
+struct Search {
+    timeout: Option<Duration>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    limit: usize,
+    solutions: usize,
+    first: Option<Board>,
+    guesses: usize,
+    depth: usize,
+    stats: SolveStats,
+}
+
+impl Search {
+    fn new(limit: usize, timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            #[cfg(feature = "std")]
+            deadline: timeout.map(|t| Instant::now() + t),
+            limit,
+            solutions: 0,
+            first: None,
+            guesses: 0,
+            depth: 0,
+            stats: SolveStats::default(),
+        }
+    }
+
+    /// Counts solutions up to the limit, failing once the deadline passes.
+    fn run(&mut self, board: &mut Board) -> Result<(), ()> {
+        #[cfg(feature = "std")]
+        if self.deadline.is_some_and(|d| Instant::now() > d) {
+            return Err(());
+        }
+        let propagations = &mut self.stats.propagations;
+        let next = board
+            .iter()
+            .filter(|(_, value)| value.is_none())
+            .map(|(loc, _)| {
+                *propagations += 1;
+                (*loc, board.available_values(loc))
+            })
+            .min_by_key(|(loc, values)| (values.len(), *loc));
+        let Some((loc, values)) = next else {
+            if board.is_solved() {
+                self.solutions += 1;
+                self.first.get_or_insert_with(|| board.clone());
+            }
+            return Ok(());
+        };
+        let guess = values.len() > 1;
+        self.depth += 1;
+        self.stats.max_depth = self.stats.max_depth.max(self.depth);
+        for value in values {
+            self.guesses += usize::from(guess);
+            self.stats.nodes += 1;
+            board.force_set(&loc, Some(value), Origin::Solved);
+            let result = self.run(board);
+            board.unfill(&loc);
+            if result.is_err() || self.solutions >= self.limit {
+                self.depth -= 1;
+                return result;
+            }
+            self.stats.backtracks += 1;
+        }
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn start(mut self, board: &Board) -> Result<Self, String> {
+        let mut board = board.clone();
+        match self.run(&mut board) {
+            Ok(()) => Ok(self),
+            Err(()) => Err(format!(
+                "Search took longer than {:?}",
+                self.timeout.unwrap_or_default()
+            )),
+        }
+    }
+}
+
+impl Board {
+    /// Counts solutions, stopping once `limit` of them are found.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.solutions(limit).0
+    }
+
+    /// Like `Board::count_solutions`, failing when it takes longer than
+    /// `timeout`.
+    #[cfg(feature = "std")]
+    pub fn count_solutions_within(&self, limit: usize, timeout: Duration) -> Result<usize, String> {
+        Ok(Search::new(limit, Some(timeout)).start(self)?.solutions)
+    }
+
+    /// Like `Board::solve`, failing when it takes longer than `timeout`.
+    #[cfg(feature = "std")]
+    pub fn solve_within(&self, timeout: Duration) -> Result<Option<Board>, String> {
+        Ok(Search::new(1, Some(timeout)).start(self)?.first)
+    }
+
+    /// Solves the board with the search behind `Board::solve_within` and
+    /// `Board::count_solutions`, also reporting the work done.
+    pub fn solve_with_stats(&self) -> (Option<Board>, SolveStats) {
+        #[cfg(feature = "std")]
+        let start = Instant::now();
+        let mut search = Search::new(1, None);
+        let mut board = self.clone();
+        let _ = search.run(&mut board);
+        #[cfg(feature = "std")]
+        {
+            search.stats.elapsed = start.elapsed();
+        }
+        (search.first, search.stats)
+    }
+
+    /// Looks for up to `limit` solutions, returning how many were found along
+    /// with the first one.
+    pub(crate) fn solutions(&self, limit: usize) -> (usize, Option<Board>) {
+        match Search::new(limit, None).start(self) {
+            Ok(search) => (search.solutions, search.first),
+            Err(_) => (0, None),
+        }
+    }
+
+    /// Rates the puzzle by filling singles first and counting the guesses
+    /// needed for the rest.
+    pub fn rate(&self) -> Result<Rating, String> {
+        self.rate_with(None)
+    }
+
+    /// Like `Board::rate`, failing when it takes longer than `timeout`.
+    #[cfg(feature = "std")]
+    pub fn rate_within(&self, timeout: Duration) -> Result<Rating, String> {
+        self.rate_with(Some(timeout))
+    }
+
+    fn rate_with(&self, timeout: Option<Duration>) -> Result<Rating, String> {
+        let mut board = self.clone();
+        let givens = board.values().filter(|v| v.is_some()).count();
+        let (mut naked_singles, mut hidden_singles) = (0, 0);
+        loop {
+            if let Some((loc, value)) = board.naked_single() {
+                naked_singles += 1;
+                board.force_set(&loc, Some(value), Origin::Solved);
+            } else if let Some((loc, value)) = board.hidden_single() {
+                hidden_singles += 1;
+                board.force_set(&loc, Some(value), Origin::Solved);
+            } else {
+                break;
+            }
+        }
+        let search = Search::new(1, timeout).start(&board)?;
+        if search.solutions == 0 {
+            return Err("Puzzle has no solution".to_string());
+        }
+        let difficulty = match (search.guesses, hidden_singles) {
+            (0, 0) => Difficulty::Easy,
+            (0, _) => Difficulty::Medium,
+            (1..=HARD_GUESSES, _) => Difficulty::Hard,
+            _ => Difficulty::Expert,
+        };
+        Ok(Rating {
+            difficulty,
+            givens,
+            naked_singles,
+            hidden_singles,
+            guesses: search.guesses,
+        })
+    }
+
+    fn naked_single(&self) -> Option<(Loc, L)> {
+        Board::gen_all_locs(self.top)
+            .into_iter()
+            .filter(|loc| self.value(loc).is_none())
+            .find_map(|loc| match self.available_values(&loc)[..] {
+                [value] => Some((loc, value)),
+                _ => None,
+            })
+    }
+
+    fn hidden_single(&self) -> Option<(Loc, L)> {
+        let (size, top) = (self.size, self.top);
+        let units = (1..=top).flat_map(|i| {
+            let (row, col) = ((i - 1) / size * size, (i - 1) % size * size);
+            [
+                (1..=top).map(|j| Loc::new(i, j)).collect::<Vec<_>>(),
+                (1..=top).map(|j| Loc::new(j, i)).collect(),
+                (1..=top)
+                    .map(|j| Loc::new(row + (j - 1) / size + 1, col + (j - 1) % size + 1))
+                    .collect(),
+            ]
+        });
+        for unit in units {
+            let empty: Vec<(Loc, Vec<L>)> = unit
+                .into_iter()
+                .filter(|loc| self.value(loc).is_none())
+                .map(|loc| (loc, self.available_values(&loc)))
+                .collect();
+            for value in 1..=top {
+                let mut places = empty.iter().filter(|(_, values)| values.contains(&value));
+                if let (Some((loc, _)), None) = (places.next(), places.next()) {
+                    return Some((*loc, value));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HARD: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+    #[test]
+    fn test_board_count_solutions() {
+        assert_eq!(
+            Board::from_line("1.3434.22143.321")
+                .unwrap()
+                .count_solutions(10),
+            1
+        );
+        assert_eq!(Board::new(2).count_solutions(10), 10);
+        assert_eq!(Board::new(2).count_solutions(1000), 288);
+        assert_eq!(
+            Board::from_line("11..............")
+                .unwrap()
+                .count_solutions(10),
+            0
+        );
+    }
+
+    #[test]
+    fn test_board_solve_with_stats() {
+        let (solution, stats) = Board::from_line("1.3434.22143.321")
+            .unwrap()
+            .solve_with_stats();
+        assert!(solution.unwrap().is_solved());
+        assert_eq!(
+            (
+                stats.nodes,
+                stats.backtracks,
+                stats.max_depth,
+                stats.propagations
+            ),
+            (3, 0, 3, 6)
+        );
+
+        let (solution, stats) = Board::new(3).solve_with_stats();
+        assert!(solution.unwrap().is_solved());
+        assert_eq!(stats.max_depth, 81);
+        assert_eq!(stats.nodes, 81 + stats.backtracks);
+
+        let (solution, stats) = Board::from_line(HARD).unwrap().solve_with_stats();
+        assert!(solution.unwrap().is_solved());
+        assert!(stats.backtracks > 0);
+        assert_eq!(stats.nodes, 60 + stats.backtracks);
+
+        let (solution, stats) = Board::from_line("11..............")
+            .unwrap()
+            .solve_with_stats();
+        assert!(solution.is_none());
+        assert_eq!(stats.backtracks, stats.nodes);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_board_solve_within() {
+        let board = Board::from_line(HARD).unwrap();
+        let solution = board
+            .solve_within(Duration::from_secs(60))
+            .unwrap()
+            .unwrap();
+        assert!(solution.is_solved());
+        assert_eq!(solution.origin(&Loc::new(1, 2)), Some(Origin::Solved));
+        assert!(board.solve_within(Duration::ZERO).is_err());
+        assert!(board.count_solutions_within(2, Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_board_rate() {
+        let rating = Board::from_line("1.3434.22143.321")
+            .unwrap()
+            .rate()
+            .unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+        assert_eq!(
+            (rating.givens, rating.naked_singles, rating.guesses),
+            (13, 3, 0)
+        );
+
+        let rating = Board::from_line(HARD).unwrap().rate().unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Expert);
+        #[cfg(feature = "std")]
+        assert!(Board::from_line(HARD)
+            .unwrap()
+            .rate_within(Duration::ZERO)
+            .is_err());
+
+        assert!(Board::from_line("11..............")
+            .unwrap()
+            .rate()
+            .is_err());
+    }
+}
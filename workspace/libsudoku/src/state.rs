@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use super::loc::{Loc, L};
+use super::prelude::*;
+use super::{Board, Origin};
+
+/// Board along with the origins of its digits and its pencil marks, for saving
+/// games in progress. `Serialize for Board` writes plain values only.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameState {
+    /// Rows of cells, each either `null` or a value with its origin.
+    pub values: Vec<Vec<Option<(L, Origin)>>>,
+    /// Explicit pencil marks, including the ones of filled cells, which show
+    /// again once the cell is cleared.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub marks: Vec<(Loc, Vec<L>)>,
+}
+
+impl From<&Board> for GameState {
+    fn from(other: &Board) -> Self {
+        let top = other.top();
+        let values = (1..=top)
+            .map(|row| {
+                (1..=top)
+                    .map(|col| {
+                        let loc = Loc::new(row, col);
+                        Some((other.value(&loc)?, other.origin(&loc)?))
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut marks: Vec<(Loc, Vec<L>)> = Board::gen_all_locs(top)
+            .into_iter()
+            .filter_map(|loc| Some((loc, other.marks(&loc)?.clone())))
+            .collect();
+        marks.sort();
+        Self { values, marks }
+    }
+}
+
+impl TryFrom<GameState> for Board {
+    type Error = String;
+
+    fn try_from(other: GameState) -> Result<Self, Self::Error> {
+        let rows = other.values.len();
+        let size =
+            Board::calc_size(rows).ok_or(format!("{} rows do not form a square board", rows))?;
+        let mut board = Board::new(size);
+        for (row, cols) in (1..=board.top()).zip(other.values) {
+            if cols.len() != rows {
+                return Err(format!("Row {} does not have {} cells", row, rows));
+            }
+            for (col, cell) in (1..=board.top()).zip(cols) {
+                let loc = Loc::new(row, col);
+                if let Some((value, origin)) = cell {
+                    if !(1..=board.top()).contains(&value) {
+                        return Err(format!("Invalid value {} in {}", value, loc));
+                    }
+                    board.force_set(&loc, Some(value), origin);
+                }
+            }
+        }
+        for (loc, marks) in other.marks {
+            if !board.contains_key(&loc) {
+                return Err(format!("{} is outside of the board", loc));
+            }
+            board.set_marks(&loc, Some(marks));
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_state() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        board.set(&Loc::new(1, 2), Some(2)).unwrap();
+        board.set_marks(&Loc::new(2, 3), Some(vec![1, 4]));
+        // r2c3 gets solved but keeps its marks.
+        let board = board.solve().unwrap();
+
+        let json = serde_json::to_value(GameState::from(&board)).unwrap();
+        assert_eq!(json["values"][0][0], serde_json::json!([1, "given"]));
+        assert_eq!(json["values"][0][1], serde_json::json!([2, "entered"]));
+        assert_eq!(json["values"][1][2], serde_json::json!([1, "solved"]));
+        assert_eq!(
+            json["marks"],
+            serde_json::json!([[{"row": 2, "col": 3}, [1, 4]]])
+        );
+
+        let state: GameState = serde_json::from_value(json).unwrap();
+        assert_eq!(Board::try_from(state), Ok(board));
+    }
+
+    #[test]
+    fn test_game_state_errors() {
+        let tests = [
+            r#"{"values": [[null, null], [null, null]]}"#,
+            r#"{"values": [[null], [null], [null], [null]]}"#,
+            r#"{"values": [[[2, "given"]]]}"#,
+            r#"{"values": [[null]], "marks": [[{"row": 2, "col": 1}, [1]]]}"#,
+        ];
+        for json in tests {
+            let state: GameState = serde_json::from_str(json).unwrap();
+            assert!(Board::try_from(state).is_err(), "{}", json);
+        }
+        assert!(serde_json::from_str::<GameState>(r#"{"values": [[[1, "guessed"]]]}"#).is_err());
+    }
+}
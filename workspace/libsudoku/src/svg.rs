@@ -0,0 +1,203 @@
+use alloc::collections::BTreeMap;
+use core::fmt::Write;
+
+use super::loc::{Loc, L};
+use super::prelude::*;
+use super::{Board, Origin};
+
+/// Settings of `Board::to_svg`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Width and height of a single cell in pixels.
+    pub cell: u32,
+    /// Whether pencil marks of empty cells are drawn.
+    pub marks: bool,
+    /// Background colours of highlighted cells, in any SVG colour syntax.
+    /// Cells outside of the board are skipped.
+    pub highlights: BTreeMap<Loc, String>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell: 48,
+            marks: false,
+            highlights: BTreeMap::new(),
+        }
+    }
+}
+
+impl SvgOptions {
+    pub fn highlight(mut self, loc: Loc, colour: &str) -> Self {
+        self.highlights.insert(loc, colour.to_string());
+        self
+    }
+}
+
+/// Escapes characters with a special meaning in XML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Board {
+    /// Renders the board as a standalone SVG image, with thick borders around
+    /// boxes, givens in bold black, digits entered by the player in blue and
+    /// solved ones in green.
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let cell = options.cell;
+        let margin = cell / 8;
+        let top = u32::from(self.top);
+        let side = top * cell + 2 * margin;
+        let at = |i: L| margin + u32::from(i - 1) * cell;
+        let mut output = String::new();
+
+        let _ = writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#,
+            side
+        );
+        let _ = writeln!(
+            output,
+            r##"<rect x="0" y="0" width="{0}" height="{0}" fill="#fff"/>"##,
+            side
+        );
+
+        let highlights = options.highlights.iter();
+        for (loc, colour) in highlights.filter(|(loc, _)| self.contains_key(loc)) {
+            let _ = writeln!(
+                output,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                at(loc.col),
+                at(loc.row),
+                cell,
+                cell,
+                escape(colour)
+            );
+        }
+
+        let font =
+            "font-family=\"sans-serif\" text-anchor=\"middle\" dominant-baseline=\"central\"";
+        for loc in Board::gen_all_locs(self.top) {
+            let (x, y) = (at(loc.col), at(loc.row));
+            if self.value(&loc).is_some() {
+                let style = match self.origin(&loc) {
+                    Some(Origin::Entered) => r##"fill="#1a5fb4""##,
+                    Some(Origin::Solved) => r##"fill="#26a269""##,
+                    _ => r##"font-weight="bold" fill="#000""##,
+                };
+                let _ = writeln!(
+                    output,
+                    r#"<text x="{}" y="{}" font-size="{}" {} {}>{}</text>"#,
+                    x + cell / 2,
+                    y + cell / 2,
+                    cell * 3 / 5,
+                    font,
+                    style,
+                    escape(&self.get_str(&loc))
+                );
+            } else if let Some(marks) = self.marks(&loc).filter(|_| options.marks) {
+                let size = u32::from(self.size);
+                let step = cell / size;
+                for mark in marks {
+                    let index = u32::from(mark - 1);
+                    let symbol = self.symbols.symbol(*mark).unwrap_or("?");
+                    let _ = writeln!(
+                        output,
+                        r##"<text x="{}" y="{}" font-size="{}" {} fill="#555">{}</text>"##,
+                        x + (index % size) * step + step / 2,
+                        y + (index / size) * step + step / 2,
+                        step * 3 / 4,
+                        font,
+                        escape(symbol)
+                    );
+                }
+            }
+        }
+
+        for i in 0..=self.top {
+            let offset = margin + u32::from(i) * cell;
+            let width = match i % self.size {
+                0 => cell / 16 + 1,
+                _ => 1,
+            };
+            let _ = writeln!(
+                output,
+                r##"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="#000" stroke-width="{3}" stroke-linecap="square"/>"##,
+                offset,
+                margin,
+                side - margin,
+                width
+            );
+            let _ = writeln!(
+                output,
+                r##"<line x1="{1}" y1="{0}" x2="{2}" y2="{0}" stroke="#000" stroke-width="{3}" stroke-linecap="square"/>"##,
+                offset,
+                margin,
+                side - margin,
+                width
+            );
+        }
+        let _ = writeln!(output, "</svg>");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols::SymbolSet;
+
+    #[test]
+    fn test_board_to_svg() {
+        let board = Board::from_line("1.3434.22143.321").unwrap();
+        let svg = board.to_svg(&SvgOptions::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"204\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<text").count(), 13);
+        assert_eq!(svg.matches("font-weight=\"bold\"").count(), 13);
+        assert_eq!(svg.matches("<line").count(), 10);
+        assert_eq!(svg.matches("stroke-width=\"4\"").count(), 6);
+    }
+
+    #[test]
+    fn test_board_to_svg_options() {
+        let mut board = Board::from_line("1.3434.22143.321").unwrap();
+        board.set(&Loc::new(1, 2), Some(2)).unwrap();
+        board.set_marks(&Loc::new(2, 3), Some(vec![1, 4]));
+
+        let options = SvgOptions::default().highlight(Loc::new(1, 2), "#ffe08a");
+        let svg = board.to_svg(&options);
+        assert_eq!(svg.matches("font-weight=\"bold\"").count(), 13);
+        assert_eq!(svg.matches("fill=\"#1a5fb4\"").count(), 1);
+        let solved = board.solve().unwrap().to_svg(&options);
+        assert_eq!(solved.matches("fill=\"#26a269\"").count(), 2);
+        assert!(svg.contains(r##"<rect x="54" y="6" width="48" height="48" fill="#ffe08a"/>"##));
+        assert!(!svg.contains("fill=\"#555\""));
+
+        let svg = board.to_svg(&SvgOptions {
+            marks: true,
+            ..options
+        });
+        assert_eq!(svg.matches("fill=\"#555\"").count(), 2);
+
+        let outside = SvgOptions::default()
+            .highlight(Loc::new(0, 1), "red")
+            .highlight(Loc::new(5, 5), "red");
+        assert!(!board.to_svg(&outside).contains("fill=\"red\""));
+    }
+
+    #[test]
+    fn test_board_to_svg_escape() {
+        let mut board = Board::new(1);
+        board
+            .set_symbols(SymbolSet::custom(vec!["<&>".to_string()]).unwrap())
+            .unwrap();
+        board.set(&Loc::new(1, 1), Some(1)).unwrap();
+        assert!(board
+            .to_svg(&SvgOptions::default())
+            .contains(">&lt;&amp;&gt;</text>"));
+    }
+}
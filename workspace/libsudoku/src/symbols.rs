@@ -0,0 +1,256 @@
+use super::loc::L;
+use super::prelude::*;
+
+const HEX: &str = "0123456789ABCDEF";
+const LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const COMPACT: &str = "123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Alphabet mapping cell values to the symbols used when reading and printing
+/// a board. Values stay numeric internally, the first symbol stands for `1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolSet {
+    symbols: Vec<String>,
+    numeric: bool,
+}
+
+impl SymbolSet {
+    /// Decimal numbers from `1` to `top`.
+    pub fn numeric(top: L) -> Self {
+        Self {
+            symbols: (1..=top).map(|v| v.to_string()).collect(),
+            numeric: true,
+        }
+    }
+
+    fn from_chars(chars: &str, top: L) -> Result<Self, String> {
+        if usize::from(top) > chars.len() {
+            return Err(format!(
+                "Alphabet '{}' is too short for {} values",
+                chars, top
+            ));
+        }
+        Ok(Self {
+            symbols: chars
+                .chars()
+                .take(top.into())
+                .map(|c| c.to_string())
+                .collect(),
+            numeric: false,
+        })
+    }
+
+    /// Hexadecimal digits starting from `0`, as used by 16x16 puzzles.
+    pub fn hex(top: L) -> Result<Self, String> {
+        Self::from_chars(HEX, top)
+    }
+
+    /// Capital letters starting from `A`.
+    pub fn letters(top: L) -> Result<Self, String> {
+        Self::from_chars(LETTERS, top)
+    }
+
+    /// Single characters for every value, so each cell takes exactly one
+    /// character: digits up to 9, followed by capital and small letters.
+    pub fn compact(top: L) -> Result<Self, String> {
+        if top <= 9 {
+            Ok(Self::numeric(top))
+        } else {
+            Self::from_chars(COMPACT, top)
+        }
+    }
+
+    /// Built-in alphabets for `top` values, in the order they are tried when
+    /// reading a board without a known alphabet.
+    pub fn presets(top: L) -> Vec<Self> {
+        [
+            Ok(Self::numeric(top)),
+            Self::compact(top),
+            Self::hex(top),
+            Self::letters(top),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Checks whether every symbol is a single character.
+    pub fn is_compact(&self) -> bool {
+        self.symbols.iter().all(|s| s.chars().count() == 1)
+    }
+
+    /// Arbitrary symbols, which have to be unique, non-empty and free of whitespace.
+    pub fn custom(symbols: Vec<String>) -> Result<Self, String> {
+        if symbols.is_empty() {
+            return Err("Alphabet cannot be empty".to_string());
+        }
+        for (i, symbol) in symbols.iter().enumerate() {
+            if symbol.is_empty() || symbol.chars().any(char::is_whitespace) {
+                return Err(format!("Invalid symbol '{}'", symbol));
+            }
+            if symbols[..i].contains(symbol) {
+                return Err(format!("Duplicated symbol '{}'", symbol));
+            }
+        }
+        if L::try_from(symbols.len()).is_err() {
+            return Err(format!("Alphabet of {} symbols is too long", symbols.len()));
+        }
+        Ok(Self {
+            symbols,
+            numeric: false,
+        })
+    }
+
+    /// Picks an alphabet by name: `numeric`, `hex`, `letters`, `compact` or a
+    /// list of symbols separated by commas.
+    pub fn by_name(name: &str, top: L) -> Result<Self, String> {
+        match name {
+            "numeric" => Ok(Self::numeric(top)),
+            "hex" => Self::hex(top),
+            "letters" => Self::letters(top),
+            "compact" => Self::compact(top),
+            custom => {
+                let symbols = Self::custom(custom.split(',').map(String::from).collect())?;
+                if symbols.len() != usize::from(top) {
+                    return Err(format!(
+                        "Alphabet has {} symbols instead of {}",
+                        symbols.len(),
+                        top
+                    ));
+                }
+                Ok(symbols)
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.numeric
+    }
+
+    /// Width in characters of the longest symbol.
+    pub fn width(&self) -> usize {
+        self.symbols
+            .iter()
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn symbol(&self, value: L) -> Option<&str> {
+        self.symbols
+            .get(usize::from(value).checked_sub(1)?)
+            .map(String::as_str)
+    }
+
+    /// Finds the value of `symbol`, falling back to a case-insensitive match.
+    pub fn value(&self, symbol: &str) -> Option<L> {
+        let position = self.symbols.iter().position(|s| s == symbol).or_else(|| {
+            self.symbols
+                .iter()
+                .position(|s| s.eq_ignore_ascii_case(symbol))
+        })?;
+        L::try_from(position + 1).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols_numeric() {
+        let symbols = SymbolSet::numeric(16);
+        assert!(symbols.is_numeric());
+        assert_eq!(symbols.len(), 16);
+        assert_eq!(symbols.width(), 2);
+        assert_eq!(symbols.symbol(1), Some("1"));
+        assert_eq!(symbols.symbol(16), Some("16"));
+        assert_eq!(symbols.symbol(0), None);
+        assert_eq!(symbols.symbol(17), None);
+        assert_eq!(symbols.value("12"), Some(12));
+        assert_eq!(symbols.value("0"), None);
+    }
+
+    #[test]
+    fn test_symbols_hex() {
+        let symbols = SymbolSet::hex(16).unwrap();
+        assert!(!symbols.is_numeric());
+        assert_eq!(symbols.width(), 1);
+        assert_eq!(symbols.symbol(1), Some("0"));
+        assert_eq!(symbols.symbol(16), Some("F"));
+        assert_eq!(symbols.value("a"), Some(11));
+        assert!(SymbolSet::hex(25).is_err());
+    }
+
+    #[test]
+    fn test_symbols_letters() {
+        let symbols = SymbolSet::letters(16).unwrap();
+        assert_eq!(symbols.symbol(1), Some("A"));
+        assert_eq!(symbols.symbol(16), Some("P"));
+        assert_eq!(symbols.value("Q"), None);
+        assert_eq!(symbols.value("p"), Some(16));
+        assert!(SymbolSet::letters(36).is_err());
+    }
+
+    #[test]
+    fn test_symbols_compact() {
+        assert_eq!(SymbolSet::compact(9), Ok(SymbolSet::numeric(9)));
+        assert!(SymbolSet::compact(9).unwrap().is_compact());
+        assert!(!SymbolSet::numeric(16).is_compact());
+
+        let symbols = SymbolSet::compact(25).unwrap();
+        assert!(symbols.is_compact());
+        assert_eq!(symbols.symbol(10), Some("A"));
+        assert_eq!(symbols.symbol(25), Some("P"));
+        assert_eq!(SymbolSet::compact(49).unwrap().symbol(49), Some("n"));
+        assert!(SymbolSet::compact(64).is_err());
+    }
+
+    #[test]
+    fn test_symbols_presets() {
+        assert_eq!(
+            SymbolSet::presets(9),
+            vec![
+                SymbolSet::numeric(9),
+                SymbolSet::numeric(9),
+                SymbolSet::hex(9).unwrap(),
+                SymbolSet::letters(9).unwrap()
+            ]
+        );
+        assert_eq!(SymbolSet::presets(36).len(), 2);
+    }
+
+    #[test]
+    fn test_symbols_custom() {
+        let symbols =
+            SymbolSet::custom(vec!["★".to_string(), "♥".to_string(), "Xy".to_string()]).unwrap();
+        assert_eq!(symbols.width(), 2);
+        assert_eq!(symbols.value("♥"), Some(2));
+        assert_eq!(symbols.value("xY"), Some(3));
+
+        assert!(SymbolSet::custom(vec![]).is_err());
+        assert!(SymbolSet::custom(vec!["a".to_string(), "a".to_string()]).is_err());
+        assert!(SymbolSet::custom(vec!["a b".to_string()]).is_err());
+        assert!(SymbolSet::custom(vec!["".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_symbols_by_name() {
+        assert_eq!(SymbolSet::by_name("numeric", 4), Ok(SymbolSet::numeric(4)));
+        assert_eq!(SymbolSet::by_name("hex", 4), SymbolSet::hex(4));
+        assert_eq!(SymbolSet::by_name("letters", 4), SymbolSet::letters(4));
+        assert_eq!(SymbolSet::by_name("compact", 16), SymbolSet::compact(16));
+        assert_eq!(
+            SymbolSet::by_name("w,x,y,z", 4).unwrap().symbol(4),
+            Some("z")
+        );
+        assert!(SymbolSet::by_name("w,x,y", 4).is_err());
+    }
+}
@@ -1,16 +1,17 @@
-use super::loc::Loc;
 use super::checker::ValChecker;
+use super::loc::{Loc, L};
+use super::prelude::*;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Verifier {
-    size: u8,
+    size: L,
     rows: ValChecker,
     cols: ValChecker,
     sqrs: ValChecker,
 }
 
 impl Verifier {
-    pub fn new(size: u8) -> Self {
+    pub fn new(size: L) -> Self {
         let top = size.pow(2);
         Self {
             size,
@@ -20,13 +21,13 @@ impl Verifier {
         }
     }
 
-    pub fn set(&mut self, loc: &Loc, val: u8) {
+    pub fn set(&mut self, loc: &Loc, val: L) {
         self.rows.set(loc.row, val);
         self.cols.set(loc.col, val);
         self.sqrs.set(loc.sqr(self.size), val);
     }
 
-    pub fn unset(&mut self, loc: &Loc, val: u8) {
+    pub fn unset(&mut self, loc: &Loc, val: L) {
         self.rows.unset(loc.row, val);
         self.cols.unset(loc.col, val);
         self.sqrs.unset(loc.sqr(self.size), val);
@@ -36,7 +37,7 @@ impl Verifier {
         self.rows.is_solved() && self.cols.is_solved() && self.sqrs.is_solved()
     }
 
-    pub fn available_values(&self, loc: &Loc) -> Vec<u8> {
+    pub fn available_values(&self, loc: &Loc) -> Vec<L> {
         let rows = self.rows.available_values(loc.row);
         let cols = self.cols.available_values(loc.col);
         let sqrs = self.sqrs.available_values(loc.sqr(self.size));
@@ -59,8 +60,8 @@ impl Verifier {
 mod tests {
     use super::*;
 
-    const SIZE: u8 = 2;
-    const TOP: u8 = SIZE.pow(2);
+    const SIZE: L = 2;
+    const TOP: L = SIZE.pow(2);
 
     #[test]
     fn test_new() {
@@ -0,0 +1,280 @@
+//! Python bindings of `libsudoku`, built into the `sudoker` module with
+//! maturin.
+
+use libsudoku::formats::Puzzle;
+use libsudoku::loc::{Loc, L};
+use libsudoku::Board;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+
+create_exception!(
+    sudoker,
+    ParseError,
+    PyValueError,
+    "Raised when a puzzle cannot be read."
+);
+create_exception!(
+    sudoker,
+    MoveError,
+    PyValueError,
+    "Raised when a digit breaks the rules or a given would be changed."
+);
+
+/// Cell of a board, counted from 1.
+#[pyclass(name = "Loc", module = "sudoker", frozen, eq, ord, hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct PyLoc {
+    #[pyo3(get)]
+    row: L,
+    #[pyo3(get)]
+    col: L,
+}
+
+#[pymethods]
+impl PyLoc {
+    #[new]
+    fn new(row: L, col: L) -> Self {
+        Self { row, col }
+    }
+
+    /// Reads a cell name such as `r3c5`.
+    #[staticmethod]
+    fn parse(name: &str) -> PyResult<Self> {
+        Ok(name.parse::<Loc>().map_err(ParseError::new_err)?.into())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Loc(row={}, col={})", self.row, self.col)
+    }
+
+    fn __str__(&self) -> String {
+        format!("r{}c{}", self.row, self.col)
+    }
+}
+
+impl From<Loc> for PyLoc {
+    fn from(other: Loc) -> Self {
+        Self::new(other.row, other.col)
+    }
+}
+
+/// Cell argument, given as a `Loc`, a `(row, col)` tuple or a name like `r3c5`.
+#[derive(FromPyObject)]
+enum Cell {
+    Loc(PyLoc),
+    Tuple((L, L)),
+    Name(String),
+}
+
+impl Cell {
+    fn loc(self, board: &Board) -> PyResult<Loc> {
+        let loc = match self {
+            Cell::Loc(loc) => Loc::new(loc.row, loc.col),
+            Cell::Tuple(pair) => Loc::from(pair),
+            Cell::Name(name) => name.parse().map_err(ParseError::new_err)?,
+        };
+        match board.contains_key(&loc) {
+            true => Ok(loc),
+            false => Err(PyIndexError::new_err(format!(
+                "r{}c{} is outside of the board",
+                loc.row, loc.col
+            ))),
+        }
+    }
+}
+
+/// Sudoku board, read from a puzzle in any supported format or from a list
+/// of rows with `None` for empty cells.
+#[pyclass(name = "Board", module = "sudoker", eq)]
+#[derive(Clone, PartialEq)]
+struct PyBoard {
+    board: Board,
+}
+
+#[pymethods]
+impl PyBoard {
+    #[new]
+    fn new(puzzle: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let board = if let Ok(text) = puzzle.downcast::<PyString>() {
+            let puzzle = Puzzle::read(text.to_str()?).map_err(ParseError::new_err)?;
+            puzzle.state.unwrap_or(puzzle.board)
+        } else {
+            let rows: Vec<Vec<Option<L>>> = puzzle
+                .extract()
+                .map_err(|_| ParseError::new_err("Expected a string or a list of rows"))?;
+            let value =
+                serde_json::to_value(rows).map_err(|e| ParseError::new_err(e.to_string()))?;
+            Board::from_json(&value).map_err(ParseError::new_err)?
+        };
+        Ok(Self { board })
+    }
+
+    /// Number of rows and columns of a box.
+    #[getter]
+    fn size(&self) -> L {
+        self.board.size()
+    }
+
+    /// Number of rows, which is also the highest digit.
+    #[getter]
+    fn top(&self) -> L {
+        self.board.top()
+    }
+
+    fn solve(&self) -> Option<Self> {
+        self.board.solve().map(|board| Self { board })
+    }
+
+    fn is_solved(&self) -> bool {
+        self.board.is_solved()
+    }
+
+    fn available_values(&self, cell: Cell) -> PyResult<Vec<L>> {
+        Ok(self.board.available_values(&cell.loc(&self.board)?))
+    }
+
+    fn count_solutions(&self, limit: usize) -> usize {
+        self.board.count_solutions(limit)
+    }
+
+    fn draw(&self) -> String {
+        self.board.draw()
+    }
+
+    fn to_vec(&self) -> Vec<Vec<Option<L>>> {
+        self.board.to_vec()
+    }
+
+    fn to_line(&self) -> PyResult<String> {
+        self.board.to_line().map_err(PyValueError::new_err)
+    }
+
+    /// Filled cells holding the same digit as one of their peers.
+    fn conflicts(&self) -> Vec<PyLoc> {
+        self.board
+            .conflicts()
+            .into_iter()
+            .map(PyLoc::from)
+            .collect()
+    }
+
+    fn __getitem__(&self, cell: Cell) -> PyResult<Option<L>> {
+        Ok(self.board.value(&cell.loc(&self.board)?))
+    }
+
+    /// Places a digit following the rules, or clears the cell for `None`.
+    fn __setitem__(&mut self, cell: Cell, value: Option<L>) -> PyResult<()> {
+        let loc = cell.loc(&self.board)?;
+        self.board.try_set(&loc, value).map_err(MoveError::new_err)
+    }
+
+    fn __delitem__(&mut self, cell: Cell) -> PyResult<()> {
+        self.__setitem__(cell, None)
+    }
+
+    fn __repr__(&self) -> String {
+        match self.board.to_line() {
+            Ok(line) => format!("Board('{}')", line),
+            Err(_) => format!("<Board {0}x{0}>", self.board.top()),
+        }
+    }
+
+    fn __str__(&self) -> String {
+        self.board.draw()
+    }
+}
+
+#[pymodule]
+fn sudoker(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyBoard>()?;
+    m.add_class::<PyLoc>()?;
+    m.add("ParseError", m.py().get_type::<ParseError>())?;
+    m.add("MoveError", m.py().get_type::<MoveError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    /// Runs a Python snippet with the module importable as `sudoker`.
+    fn run(code: &str) {
+        let code = CString::new(code).unwrap();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "sudoker").unwrap();
+            sudoker(&module).unwrap();
+            let modules = py.import("sys").unwrap().getattr("modules").unwrap();
+            modules.set_item("sudoker", module).unwrap();
+            let globals = PyDict::new(py);
+            if let Err(e) = py.run(&code, Some(&globals), None) {
+                panic!("{}", e);
+            }
+        });
+    }
+
+    #[test]
+    fn test_board() {
+        run(r#"
+from sudoker import Board, Loc
+board = Board("1.3434.22143.321")
+assert (board.size, board.top) == (2, 4)
+assert board[Loc(1, 1)] == 1 and board[(1, 2)] is None
+assert board.available_values("r1c2") == [2]
+board[1, 2] = 2
+assert repr(board) == "Board('123434.22143.321')"
+solution = board.solve()
+assert solution.is_solved() and not board.is_solved()
+assert solution.to_vec()[3] == [4, 3, 2, 1]
+assert str(solution) == solution.draw()
+assert Board([[1, 2, 3, 4], [3, 4, 1, 2], [2, 1, 4, 3], [4, 3, 2, 1]]).to_vec() == solution.to_vec()
+assert Board([[None]]).count_solutions(5) == 1
+del board[1, 2]
+assert board == Board("1.3434.22143.321")
+"#);
+    }
+
+    #[test]
+    fn test_loc() {
+        run(r#"
+from sudoker import Loc
+loc = Loc.parse("R3C5")
+assert (loc.row, loc.col) == (3, 5)
+assert loc == Loc(3, 5) and len({loc, Loc(3, 5)}) == 1
+assert repr(loc) == "Loc(row=3, col=5)" and str(loc) == "r3c5"
+assert sorted([Loc(2, 1), Loc(1, 2)]) == [Loc(1, 2), Loc(2, 1)]
+"#);
+    }
+
+    #[test]
+    fn test_errors() {
+        run(r#"
+from sudoker import Board, Loc, ParseError, MoveError
+def raises(error, call):
+    try:
+        call()
+    except error:
+        return True
+    return False
+board = Board("1.3434.22143.321")
+assert raises(ParseError, lambda: Board("12"))
+assert raises(ParseError, lambda: Board([[1, 2]]))
+assert raises(ParseError, lambda: Board(7))
+assert raises(ParseError, lambda: Loc.parse("x"))
+assert raises(ValueError, lambda: Board("12"))
+assert raises(IndexError, lambda: board[5, 1])
+assert raises(MoveError, lambda: board.__setitem__((1, 2), 4))
+assert raises(MoveError, lambda: board.__delitem__((1, 1)))
+assert Board("11..............").solve() is None
+board = Board("11..............")
+assert board.conflicts() == [Loc(1, 1), Loc(1, 2)]
+large = Board([[None] * 64] * 64)
+assert repr(large) == "<Board 64x64>" and raises(ValueError, large.to_line)
+"#);
+    }
+}
@@ -1,3 +1,289 @@
-fn main() {
-    println!("Hello, world!");
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use libsudoku::ansi::AnsiOptions;
+use libsudoku::batch::{Batch, Status};
+use libsudoku::formats::{Format, Puzzle};
+use libsudoku::loc::Loc;
+use libsudoku::state::GameState;
+use libsudoku::Board;
+use serve::Limits;
+
+mod play;
+mod repl;
+mod rpc;
+mod serve;
+
+#[derive(Parser)]
+#[command(version, about = "Solve and play sudoku puzzles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Draw a puzzle in the terminal
+    Draw {
+        /// Puzzle file, or `-` for stdin
+        file: PathBuf,
+        /// Format of the puzzle, detected from its content by default
+        #[arg(short, long)]
+        format: Option<Format>,
+        /// Draw the solution, with solved cells told apart from givens
+        #[arg(short, long)]
+        solve: bool,
+        /// Print statistics of the search to stderr
+        #[arg(long, requires = "solve")]
+        stats: bool,
+        /// When to use colors
+        #[arg(long, value_enum, default_value_t = Color::Auto)]
+        color: Color,
+        /// Highlight cells breaking the rules
+        #[arg(long)]
+        conflicts: bool,
+        /// Mark a cell and its peers, e.g. `r3c5`
+        #[arg(long)]
+        select: Option<Loc>,
+        /// Show candidates of empty cells
+        #[arg(long)]
+        candidates: bool,
+    },
+    /// Play a puzzle full screen in the terminal
+    Play {
+        /// Puzzle file in any supported format, or a saved game
+        file: PathBuf,
+        /// Format of the puzzle, detected from its content by default
+        #[arg(short, long)]
+        format: Option<Format>,
+        /// Where games are saved, `<file>.game.json` by default
+        #[arg(long)]
+        save: Option<PathBuf>,
+    },
+    /// Edit a board with commands read from stdin, see `help` inside
+    Repl {
+        /// Puzzle loaded before the first command
+        file: Option<PathBuf>,
+        /// Format of the puzzle, detected from its content by default
+        #[arg(short, long)]
+        format: Option<Format>,
+    },
+    /// Solve puzzles read one per line on all CPU cores, printing the line,
+    /// status, time in microseconds and solution or error of each in input
+    /// order. Solutions too large for a single line are printed as JSON rows
+    Batch {
+        /// File of puzzles in any supported single line format, or `-` for
+        /// stdin
+        file: PathBuf,
+        /// Worker threads, one per CPU core for 0
+        #[arg(short, long, default_value_t = 0)]
+        threads: usize,
+    },
+    /// Answer JSON-RPC 2.0 requests, one per line on stdin, keeping named
+    /// boards between calls
+    Rpc {
+        /// Longest time spent solving a board in a single call, in
+        /// milliseconds
+        #[arg(long, default_value_t = 2000)]
+        timeout_ms: u64,
+    },
+    /// Answer HTTP requests to solve, validate, rate, count and render boards
+    Serve {
+        /// Port on localhost
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Largest accepted request body, in bytes
+        #[arg(long, default_value_t = Limits::default().max_body)]
+        max_body: usize,
+        /// Longest time spent on a single request, in milliseconds
+        #[arg(long, default_value_t = 2000)]
+        timeout_ms: u64,
+        /// Requests answered at the same time
+        #[arg(long, default_value_t = Limits::default().workers)]
+        workers: usize,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Reads a file, or stdin for `-`.
+fn read_text(path: &Path) -> Result<String, String> {
+    match path.to_str() {
+        Some("-") => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| e.to_string())?;
+            Ok(text)
+        }
+        _ => fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// Parses a puzzle, detecting its format unless given.
+fn parse_puzzle(text: &str, format: Option<Format>) -> Result<Puzzle, String> {
+    match format {
+        Some(format) => Puzzle::read_as(text, format),
+        None => Puzzle::read(text),
+    }
+}
+
+/// Reads a puzzle from a file or stdin, detecting its format unless given.
+fn read_puzzle(path: &Path, format: Option<Format>) -> Result<Puzzle, String> {
+    parse_puzzle(&read_text(path)?, format)
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    match cli.command {
+        Command::Draw {
+            file,
+            format,
+            solve,
+            stats,
+            color,
+            conflicts,
+            select,
+            candidates,
+        } => {
+            let puzzle = read_puzzle(&file, format)?;
+            let mut board = puzzle.state.clone().unwrap_or(puzzle.board.clone());
+            if solve {
+                let (solution, search) = board.solve_with_stats();
+                if stats {
+                    eprintln!(
+                        "nodes: {}, backtracks: {}, max depth: {}, propagations: {}, time: {:.3}ms",
+                        search.nodes,
+                        search.backtracks,
+                        search.max_depth,
+                        search.propagations,
+                        search.elapsed.as_secs_f64() * 1000.0
+                    );
+                }
+                board = solution.ok_or("Puzzle has no solution")?;
+            }
+            let mut options = AnsiOptions::for_stdout();
+            options.color = match color {
+                Color::Auto => options.color,
+                Color::Always => true,
+                Color::Never => false,
+            };
+            options.conflicts = conflicts;
+            options.selected = select;
+            options.candidates = candidates;
+            print!("{}", board.draw_ansi(&options));
+        }
+        Command::Play { file, format, save } => {
+            let text = read_text(&file)?;
+            let (board, saved) = match serde_json::from_str::<GameState>(&text) {
+                Ok(state) if format.is_none() => (Board::try_from(state)?, true),
+                _ => {
+                    let puzzle = parse_puzzle(&text, format)?;
+                    (puzzle.state.unwrap_or(puzzle.board), false)
+                }
+            };
+            let save = save.unwrap_or_else(|| match saved {
+                true => file.clone(),
+                false => file.with_extension("game.json"),
+            });
+            play::play(play::Game::new(board, save))?;
+        }
+        Command::Repl { file, format } => {
+            let board = match file {
+                Some(file) => {
+                    let puzzle = read_puzzle(&file, format)?;
+                    Some(puzzle.state.unwrap_or(puzzle.board))
+                }
+                None => None,
+            };
+            let stdin = io::stdin();
+            let prompt = stdin.is_terminal();
+            let failed =
+                repl::Repl::new(board).run(stdin.lock(), io::stdout(), io::stderr(), prompt)?;
+            if failed > 0 {
+                return Err(format!("{} commands failed", failed));
+            }
+        }
+        Command::Batch { file, threads } => {
+            let reader: Box<dyn BufRead> = match file.to_str() {
+                Some("-") => Box::new(io::stdin().lock()),
+                _ => Box::new(BufReader::new(
+                    File::open(&file).map_err(|e| format!("{}: {}", file.display(), e))?,
+                )),
+            };
+            let start = Instant::now();
+            let mut counts = [0usize; Status::ALL.len()];
+            let mut output = BufWriter::new(io::stdout().lock());
+            for outcome in Batch::new(reader, threads) {
+                let outcome = outcome.map_err(|e| e.to_string())?;
+                counts[outcome.status as usize] += 1;
+                let detail = match (outcome.solution, outcome.error) {
+                    (Some(solution), _) => solution
+                        .to_line()
+                        .unwrap_or_else(|_| serde_json::Value::from(&solution).to_string()),
+                    (None, Some(error)) => error,
+                    (None, None) => String::new(),
+                };
+                writeln!(
+                    output,
+                    "{}\t{}\t{}\t{}",
+                    outcome.line,
+                    outcome.status.name(),
+                    outcome.elapsed.as_micros(),
+                    detail
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            output.flush().map_err(|e| e.to_string())?;
+            let summary: Vec<String> = Status::ALL
+                .iter()
+                .map(|status| format!("{} {}", counts[*status as usize], status.name()))
+                .collect();
+            eprintln!(
+                "{} puzzles in {:.3}s: {}",
+                counts.iter().sum::<usize>(),
+                start.elapsed().as_secs_f64(),
+                summary.join(", ")
+            );
+        }
+        Command::Rpc { timeout_ms } => rpc::Session::new(Duration::from_millis(timeout_ms))
+            .run(io::stdin().lock(), io::stdout())?,
+        Command::Serve {
+            port,
+            max_body,
+            timeout_ms,
+            workers,
+        } => {
+            let server = serve::listen(port)?;
+            if let Some(addr) = server.server_addr().to_ip() {
+                eprintln!("Listening on http://{}", addr);
+            }
+            let limits = Limits {
+                max_body,
+                timeout: Duration::from_millis(timeout_ms),
+                workers,
+                ..Limits::default()
+            };
+            serve::serve(server, limits);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
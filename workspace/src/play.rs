@@ -0,0 +1,262 @@
+use std::fs;
+use std::io::{self, Write};
+use std::panic;
+use std::path::PathBuf;
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use libsudoku::ansi::AnsiOptions;
+use libsudoku::history::History;
+use libsudoku::loc::{Loc, L};
+use libsudoku::state::GameState;
+use libsudoku::{Board, Origin};
+
+const HELP: &str = "arrows move · digits place · space clears · tab pencil marks · ? hint · \
+                    ^Z undo · ^Y redo · ^S save · esc quits";
+
+/// Game in progress in the terminal, with its cursor and status line.
+pub struct Game {
+    history: History,
+    cursor: Loc,
+    pencil: bool,
+    message: String,
+    save: PathBuf,
+}
+
+impl Game {
+    pub fn new(board: Board, save: PathBuf) -> Self {
+        Self {
+            history: History::new(board),
+            cursor: Loc::new(1, 1),
+            pencil: false,
+            message: String::new(),
+            save,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        self.history.board()
+    }
+
+    /// Applies a key press, returning `false` once the player quits.
+    pub fn handle(&mut self, key: KeyEvent) -> bool {
+        let top = self.board().top();
+        let (row, col) = (self.cursor.row, self.cursor.col);
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        self.message.clear();
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Char('c') if control => return false,
+            KeyCode::Char('z') if control => self.undo(),
+            KeyCode::Char('y') if control => self.redo(),
+            KeyCode::Char('s') if control => self.save(),
+            KeyCode::Up => self.cursor = Loc::new(if row > 1 { row - 1 } else { top }, col),
+            KeyCode::Down => self.cursor = Loc::new(row % top + 1, col),
+            KeyCode::Left => self.cursor = Loc::new(row, if col > 1 { col - 1 } else { top }),
+            KeyCode::Right => self.cursor = Loc::new(row, col % top + 1),
+            KeyCode::Tab => self.pencil = !self.pencil,
+            KeyCode::Char('?') => self.hint(),
+            KeyCode::Char(' ') | KeyCode::Backspace | KeyCode::Delete => self.place(None),
+            KeyCode::Char(c) => match self.board().symbols().value(&c.to_string()) {
+                Some(value) if self.pencil => self.toggle_mark(value),
+                Some(value) => self.place(Some(value)),
+                None => self.message = format!("'{}' is not a digit of this puzzle", c),
+            },
+            _ => {}
+        }
+        true
+    }
+
+    fn place(&mut self, value: Option<L>) {
+        let loc = self.cursor;
+        match self.history.set(&loc, value) {
+            Ok(()) => self.check_solved(),
+            Err(e) => self.message = e,
+        }
+    }
+
+    /// Toggles a pencil mark, starting from all candidates of the cell.
+    fn toggle_mark(&mut self, value: L) {
+        let loc = self.cursor;
+        if self.board().value(&loc).is_some() {
+            self.message = "Pencil marks are only kept for empty cells".to_string();
+            return;
+        }
+        let mut marks = self.board().candidates(&loc);
+        match marks.iter().position(|v| *v == value) {
+            Some(i) => {
+                marks.remove(i);
+            }
+            None => marks.push(value),
+        }
+        self.history.set_marks(&loc, Some(marks));
+    }
+
+    fn hint(&mut self) {
+        match self.board().hint() {
+            Some((loc, value)) => {
+                self.history.force_set(&loc, Some(value), Origin::Solved);
+                self.cursor = loc;
+                self.check_solved();
+            }
+            None => self.message = "No hint available".to_string(),
+        }
+    }
+
+    fn undo(&mut self) {
+        if self.history.undo().is_none() {
+            self.message = "Nothing to undo".to_string();
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.history.redo().is_none() {
+            self.message = "Nothing to redo".to_string();
+        }
+    }
+
+    fn check_solved(&mut self) {
+        if self.board().is_solved() {
+            self.message = "Solved, well done!".to_string();
+        }
+    }
+
+    fn save(&mut self) {
+        let state = GameState::from(self.board());
+        let result = serde_json::to_string(&state)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(&self.save, json + "\n").map_err(|e| e.to_string()));
+        self.message = match result {
+            Ok(()) => format!("Saved to {}", self.save.display()),
+            Err(e) => format!("{}: {}", self.save.display(), e),
+        };
+    }
+
+    /// Screen contents, with `\r\n` line ends for raw mode.
+    fn render(&self) -> String {
+        let options = AnsiOptions {
+            color: true,
+            conflicts: true,
+            selected: Some(self.cursor),
+            candidates: self.pencil,
+        };
+        let mode = match self.pencil {
+            true => "pencil marks",
+            false => "digits",
+        };
+        let screen = format!(
+            "{}\nr{}c{} · {} · {}\n{}\n",
+            self.board().draw_ansi(&options),
+            self.cursor.row,
+            self.cursor.col,
+            mode,
+            self.message,
+            HELP
+        );
+        screen.replace('\n', "\r\n")
+    }
+}
+
+/// Leaves the alternate screen and raw mode.
+fn restore_terminal() {
+    let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+}
+
+/// Runs the game full screen until the player quits. A panic restores the
+/// terminal before its message is printed.
+pub fn play(mut game: Game) -> Result<(), String> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+    let report = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        report(info);
+    }));
+    let result = execute!(stdout, EnterAlternateScreen, Hide)
+        .and_then(|_| event_loop(&mut game, &mut stdout));
+    let _ = panic::take_hook();
+    restore_terminal();
+    result.map_err(|e| e.to_string())
+}
+
+fn event_loop(game: &mut Game, stdout: &mut io::Stdout) -> io::Result<()> {
+    loop {
+        queue!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
+        write!(stdout, "{}", game.render())?;
+        stdout.flush()?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !game.handle(key) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(game: &mut Game, code: KeyCode) -> bool {
+        game.handle(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn control(game: &mut Game, c: char) -> bool {
+        game.handle(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+    }
+
+    /// Game saved to a file of its own, so that tests can run in parallel.
+    fn game(name: &str) -> Game {
+        let board = Board::from_line("1.3434.22143.321").unwrap();
+        let file = format!("sudoker-play-{}-{}.json", name, std::process::id());
+        Game::new(board, std::env::temp_dir().join(file))
+    }
+
+    #[test]
+    fn test_game_keys() {
+        let mut game = game("keys");
+        press(&mut game, KeyCode::Right);
+        press(&mut game, KeyCode::Char('4'));
+        assert!(!game.board().conflicts().is_empty());
+        press(&mut game, KeyCode::Char('2'));
+        control(&mut game, 'z');
+        assert_eq!(game.board().value(&Loc::new(1, 2)), Some(4));
+        control(&mut game, 'y');
+        press(&mut game, KeyCode::Left);
+        press(&mut game, KeyCode::Char(' '));
+        assert!(game.message.contains("given"), "{}", game.message);
+        press(&mut game, KeyCode::Char('x'));
+        assert!(!game.message.is_empty());
+
+        press(&mut game, KeyCode::Up);
+        press(&mut game, KeyCode::Tab);
+        press(&mut game, KeyCode::Char('4'));
+        assert_eq!(game.board().marks(&Loc::new(4, 1)), Some(&vec![]));
+        press(&mut game, KeyCode::Char('4'));
+        assert_eq!(game.board().marks(&Loc::new(4, 1)), Some(&vec![4]));
+
+        press(&mut game, KeyCode::Char('?'));
+        assert_eq!(game.cursor, Loc::new(2, 3));
+        press(&mut game, KeyCode::Char('?'));
+        assert!(game.board().is_solved());
+        assert_eq!(game.message, "Solved, well done!");
+        assert!(game.render().contains("r4c1 · pencil marks"));
+        assert!(!press(&mut game, KeyCode::Esc));
+    }
+
+    #[test]
+    fn test_game_save() {
+        let mut game = game("save");
+        press(&mut game, KeyCode::Right);
+        press(&mut game, KeyCode::Char('2'));
+        control(&mut game, 's');
+        assert!(game.message.starts_with("Saved to"));
+
+        let text = fs::read_to_string(&game.save).unwrap();
+        let state: GameState = serde_json::from_str(&text).unwrap();
+        assert_eq!(&Board::try_from(state).unwrap(), game.board());
+        fs::remove_file(&game.save).unwrap();
+    }
+}
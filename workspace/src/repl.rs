@@ -0,0 +1,286 @@
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use libsudoku::formats::{Format, Puzzle};
+use libsudoku::history::History;
+use libsudoku::loc::{Loc, L};
+use libsudoku::{Board, Origin};
+
+const HELP: &str = "\
+load FILE [FORMAT]   read a puzzle, detecting its format unless given
+set CELL DIGIT       place a digit following the rules, e.g. `set r3c5 7`
+clear CELL           empty a cell
+cand CELL            list the candidates of a cell
+draw                 print the board
+solve                fill in the solution, one change per cell
+undo                 revert the last change
+redo                 apply again the last change reverted
+checkpoint NAME      remember the board under a name
+restore NAME         go back or forward to a checkpoint
+save FILE [FORMAT]   write the board, picking the format from the extension
+                     or the file it replaces
+help                 show this message
+quit                 leave
+";
+
+/// Board edited by commands, one per line, with its changes recorded in a
+/// `History` like in `play`. Loading a puzzle starts a new history.
+#[derive(Default)]
+pub struct Repl {
+    history: Option<History>,
+}
+
+impl Repl {
+    pub fn new(board: Option<Board>) -> Self {
+        Self {
+            history: board.map(History::new),
+        }
+    }
+
+    fn history(&mut self) -> Result<&mut History, String> {
+        self.history.as_mut().ok_or("No puzzle loaded".to_string())
+    }
+
+    fn board(&self) -> Result<&Board, String> {
+        match self.history.as_ref() {
+            Some(history) => Ok(history.board()),
+            None => Err("No puzzle loaded".to_string()),
+        }
+    }
+
+    /// Cell named by `arg`, which has to be on the board.
+    fn cell(&self, arg: Option<&str>) -> Result<Loc, String> {
+        let loc = arg.ok_or("Missing cell")?.parse::<Loc>()?;
+        match self.board()?.contains_key(&loc) {
+            true => Ok(loc),
+            false => Err(format!("{} is outside of the board", loc)),
+        }
+    }
+
+    fn digit(&self, arg: Option<&str>) -> Result<L, String> {
+        let arg = arg.ok_or("Missing digit")?;
+        self.board()?
+            .symbols()
+            .value(arg)
+            .ok_or(format!("Invalid digit '{}'", arg))
+    }
+
+    /// Executes a single command, returning its output.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = words.split_first().ok_or("Missing command")?;
+        let arity = match *command {
+            "load" | "set" | "save" => 2,
+            "clear" | "cand" | "checkpoint" | "restore" => 1,
+            "draw" | "solve" | "undo" | "redo" | "help" => 0,
+            _ => return Err(format!("Unknown command '{}', try `help`", command)),
+        };
+        if let Some(arg) = args.get(arity) {
+            return Err(format!("Unexpected argument '{}'", arg));
+        }
+        let mut args = args.iter().copied();
+        let output = match *command {
+            "load" => {
+                let path = args.next().ok_or("Missing file")?;
+                let format = args.next().map(str::parse).transpose()?;
+                let puzzle = crate::read_puzzle(Path::new(path), format)?;
+                let board = puzzle.state.unwrap_or(puzzle.board);
+                self.history = Some(History::new(board));
+                String::new()
+            }
+            "set" => {
+                let loc = self.cell(args.next())?;
+                let value = self.digit(args.next())?;
+                self.history()?.try_set(&loc, Some(value))?;
+                String::new()
+            }
+            "clear" => {
+                let loc = self.cell(args.next())?;
+                self.history()?.try_set(&loc, None)?;
+                String::new()
+            }
+            "cand" => {
+                let loc = self.cell(args.next())?;
+                let board = self.board()?;
+                let values: Vec<&str> = board
+                    .candidates(&loc)
+                    .into_iter()
+                    .filter_map(|v| board.symbols().symbol(v))
+                    .collect();
+                match board.value(&loc) {
+                    Some(_) => format!("{} is filled\n", loc),
+                    None => format!("{}\n", values.join(" ")),
+                }
+            }
+            "draw" => self.board()?.draw(),
+            "solve" => {
+                let history = self.history()?;
+                let solution = history.board().solve().ok_or("Puzzle has no solution")?;
+                for (loc, value) in solution.iter() {
+                    if history.board().value(loc).is_none() {
+                        history.force_set(loc, *value, Origin::Solved);
+                    }
+                }
+                String::new()
+            }
+            "undo" => {
+                self.history()?.undo().ok_or("Nothing to undo")?;
+                String::new()
+            }
+            "redo" => {
+                self.history()?.redo().ok_or("Nothing to redo")?;
+                String::new()
+            }
+            "checkpoint" => {
+                let name = args.next().ok_or("Missing name")?;
+                self.history()?.checkpoint(name);
+                String::new()
+            }
+            "restore" => {
+                let name = args.next().ok_or("Missing name")?;
+                self.history()?.restore(name)?;
+                String::new()
+            }
+            "save" => {
+                let path = Path::new(args.next().ok_or("Missing file")?);
+                let format = match args.next() {
+                    Some(name) => name.parse()?,
+                    None => {
+                        let extension = path.extension().and_then(|e| e.to_str());
+                        let existing = fs::read_to_string(path).unwrap_or_default();
+                        extension
+                            .and_then(|e| Format::from_file(e, &existing))
+                            .ok_or("Cannot tell the format from the extension, pass one")?
+                    }
+                };
+                let board = self.board()?;
+                let puzzle = Puzzle {
+                    board: board.givens(),
+                    state: Some(board.clone()),
+                    metadata: Vec::new(),
+                };
+                fs::write(path, puzzle.write(format)?)
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                String::new()
+            }
+            "help" => HELP.to_string(),
+            _ => unreachable!("unknown commands are refused above"),
+        };
+        Ok(output)
+    }
+
+    /// Executes commands until `quit` or the end of `input`, skipping blank
+    /// lines and `#` comments. Errors are reported on `errors` along with
+    /// their line number, and counted.
+    pub fn run(
+        &mut self,
+        input: impl BufRead,
+        mut output: impl Write,
+        mut errors: impl Write,
+        prompt: bool,
+    ) -> Result<usize, String> {
+        let mut failed = 0;
+        let mut lines = input.lines().enumerate();
+        loop {
+            if prompt {
+                write!(output, "> ").map_err(|e| e.to_string())?;
+                output.flush().map_err(|e| e.to_string())?;
+            }
+            let Some((number, line)) = lines.next() else {
+                break;
+            };
+            let line = line.map_err(|e| e.to_string())?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line == "quit" || line == "exit" {
+                break;
+            }
+            match self.execute(line) {
+                Ok(text) => write!(output, "{}", text),
+                Err(e) => {
+                    failed += 1;
+                    writeln!(errors, "error: line {}: {}", number + 1, e)
+                }
+            }
+            .map_err(|e| e.to_string())?;
+        }
+        Ok(failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl() -> Repl {
+        Repl::new(Some(Board::from_line("1.3434.22143.321").unwrap()))
+    }
+
+    #[test]
+    fn test_repl_commands() {
+        let mut repl = repl();
+        assert_eq!(repl.execute("cand r1c2"), Ok("2\n".to_string()));
+        assert_eq!(repl.execute("set r1c2 2"), Ok(String::new()));
+        assert_eq!(
+            repl.execute("cand r1c2"),
+            Ok("r1c2 is filled\n".to_string())
+        );
+        assert!(repl.execute("set r2c3 4").is_err());
+        assert!(repl.execute("clear r1c1").is_err());
+        assert!(repl.execute("set r2c3").is_err());
+        assert!(repl.execute("set r2c3 1 2").is_err());
+        assert!(repl.execute("set x1 1").is_err());
+        assert_eq!(
+            repl.execute("set r9c9 1"),
+            Err("r9c9 is outside of the board".to_string())
+        );
+        assert!(repl.execute("clear r9c9").is_err());
+        assert!(repl.execute("cand r9c9").is_err());
+        assert!(repl.execute("fly").is_err());
+
+        repl.execute("checkpoint filled").unwrap();
+        repl.execute("solve").unwrap();
+        assert!(repl.board().unwrap().is_solved());
+        repl.execute("undo").unwrap();
+        assert!(!repl.board().unwrap().is_solved());
+        repl.execute("redo").unwrap();
+        assert!(repl.board().unwrap().is_solved());
+        assert_eq!(repl.execute("redo"), Err("Nothing to redo".to_string()));
+        repl.execute("restore filled").unwrap();
+        assert_eq!(repl.board().unwrap().value(&Loc::new(2, 3)), None);
+        assert!(repl.execute("restore solved").is_err());
+        repl.execute("clear r1c2").unwrap();
+        repl.execute("undo").unwrap();
+        repl.execute("undo").unwrap();
+        assert_eq!(
+            repl.execute("draw"),
+            Ok(Board::from_line("1.3434.22143.321").unwrap().draw())
+        );
+        assert_eq!(repl.execute("undo"), Err("Nothing to undo".to_string()));
+        assert!(Repl::default().execute("draw").is_err());
+        assert!(Repl::default().execute("undo").is_err());
+
+        let mut board = Board::new(2);
+        board.set_marks(&Loc::new(1, 1), Some(vec![2, 3]));
+        let mut repl = Repl::new(Some(board));
+        assert_eq!(repl.execute("cand r1c1"), Ok("2 3\n".to_string()));
+    }
+
+    #[test]
+    fn test_repl_run() {
+        let script = "# bug report\n\nset r1c2 2\nset r1c2 5\ncand r2c3\nquit\ndraw\n";
+        let (mut output, mut errors) = (Vec::new(), Vec::new());
+        let failed = repl()
+            .run(script.as_bytes(), &mut output, &mut errors, false)
+            .unwrap();
+        assert_eq!(failed, 1);
+        assert_eq!(String::from_utf8(output).unwrap(), "1\n");
+        assert_eq!(
+            String::from_utf8(errors).unwrap(),
+            "error: line 4: Invalid digit '5'\n"
+        );
+    }
+}
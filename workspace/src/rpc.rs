@@ -0,0 +1,435 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+use libsudoku::ansi::AnsiOptions;
+use libsudoku::formats::Puzzle;
+use libsudoku::loc::{Loc, L};
+use libsudoku::svg::SvgOptions;
+use libsudoku::Board;
+use serde_json::{json, Value};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Request was understood but cannot be carried out, e.g. an unknown board.
+const FAILED: i64 = -32000;
+
+/// Error object of a JSON-RPC response.
+#[derive(Debug, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn params(message: impl Into<String>) -> Self {
+        RpcError::new(INVALID_PARAMS, message)
+    }
+
+    fn failed(message: impl Into<String>) -> Self {
+        RpcError::new(FAILED, message)
+    }
+}
+
+fn text<'a>(params: &'a Value, key: &str) -> Result<&'a str, RpcError> {
+    params[key]
+        .as_str()
+        .ok_or(RpcError::params(format!("Missing string '{}'", key)))
+}
+
+/// Reads a cell given either as `"r3c5"` or as `{"row": 3, "col": 5}`.
+fn cell(params: &Value) -> Result<Loc, RpcError> {
+    let loc = match &params["cell"] {
+        Value::String(s) => s.parse().map_err(RpcError::params)?,
+        Value::Null => return Err(RpcError::params("Missing 'cell'")),
+        value => {
+            serde_json::from_value(value.clone()).map_err(|e| RpcError::params(e.to_string()))?
+        }
+    };
+    Ok(loc)
+}
+
+/// Boards kept by name between calls of a JSON-RPC 2.0 session, with one
+/// request or batch per line.
+pub struct Session {
+    boards: BTreeMap<String, Board>,
+    /// Longest time spent solving a board in a single call.
+    timeout: Duration,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(2))
+    }
+}
+
+impl Session {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            boards: BTreeMap::new(),
+            timeout,
+        }
+    }
+
+    /// Board named by the `name` parameter, or passed inline as `board`.
+    /// Inline boards may repeat givens, like named ones edited without checks.
+    fn board(&self, params: &Value) -> Result<Board, RpcError> {
+        if !params["board"].is_null() {
+            return Board::from_json_unchecked(&params["board"]).map_err(RpcError::params);
+        }
+        let name = text(params, "name")?;
+        self.boards
+            .get(name)
+            .cloned()
+            .ok_or(RpcError::failed(format!("Unknown board '{}'", name)))
+    }
+
+    fn board_mut(&mut self, params: &Value) -> Result<&mut Board, RpcError> {
+        let name = text(params, "name")?;
+        self.boards
+            .get_mut(name)
+            .ok_or(RpcError::failed(format!("Unknown board '{}'", name)))
+    }
+
+    fn dispatch(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        let result = match method {
+            "load" => {
+                let name = text(params, "name")?.to_string();
+                let board = match params["text"].as_str() {
+                    Some(text) => {
+                        let puzzle = match params["format"].as_str() {
+                            Some(format) => {
+                                Puzzle::read_as(text, format.parse().map_err(RpcError::params)?)
+                            }
+                            None => Puzzle::read(text),
+                        }
+                        .map_err(RpcError::params)?;
+                        puzzle.state.unwrap_or(puzzle.board)
+                    }
+                    None => self.board(&json!({ "board": params["board"] }))?,
+                };
+                self.boards.insert(name, board);
+                Value::Null
+            }
+            "unload" => {
+                self.boards
+                    .remove(text(params, "name")?)
+                    .ok_or(RpcError::failed("Unknown board"))?;
+                Value::Null
+            }
+            "list" => json!(self.boards.keys().collect::<Vec<_>>()),
+            "get" => json!(self.board(params)?),
+            "set" => {
+                let loc = cell(params)?;
+                let value = match &params["value"] {
+                    Value::Null => None,
+                    value => Some(
+                        value
+                            .as_u64()
+                            .and_then(|v| L::try_from(v).ok())
+                            .ok_or(RpcError::params("'value' must be a digit or null"))?,
+                    ),
+                };
+                let check = params["check"].as_bool().unwrap_or(true);
+                let board = self.board_mut(params)?;
+                if !board.contains_key(&loc) {
+                    return Err(RpcError::params(format!("{} is outside of the board", loc)));
+                }
+                if value.is_some_and(|v| !(1..=board.top()).contains(&v)) {
+                    return Err(RpcError::params(format!(
+                        "'value' must be between 1 and {}",
+                        board.top()
+                    )));
+                }
+                match check {
+                    true => board.try_set(&loc, value),
+                    false => board.set(&loc, value),
+                }
+                .map_err(RpcError::failed)?;
+                Value::Null
+            }
+            "solve" => json!(self
+                .board(params)?
+                .solve_within(self.timeout)
+                .map_err(RpcError::failed)?
+                .ok_or(RpcError::failed("Puzzle has no solution"))?),
+            "candidates" => {
+                let board = self.board(params)?;
+                let loc = cell(params)?;
+                if !board.contains_key(&loc) {
+                    return Err(RpcError::params(format!("{} is outside of the board", loc)));
+                }
+                json!(board.candidates(&loc))
+            }
+            "hint" => match self.board(params)?.hint() {
+                Some((loc, value)) => json!({ "cell": loc, "value": value }),
+                None => Value::Null,
+            },
+            "validate" => {
+                let board = self.board(params)?;
+                let conflicts = board.conflicts();
+                json!({
+                    "valid": conflicts.is_empty(),
+                    "solved": board.is_solved(),
+                    "conflicts": conflicts,
+                })
+            }
+            "render" => {
+                let board = self.board(params)?;
+                match params["format"].as_str().unwrap_or("text") {
+                    "text" => json!(board.draw()),
+                    "ansi" => json!(board.draw_ansi(&AnsiOptions {
+                        color: true,
+                        conflicts: true,
+                        ..AnsiOptions::default()
+                    })),
+                    "svg" => json!(board.to_svg(&SvgOptions::default())),
+                    other => {
+                        return Err(RpcError::params(format!(
+                            "Unknown render format '{}'",
+                            other
+                        )))
+                    }
+                }
+            }
+            _ => {
+                return Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("Unknown method '{}'", method),
+                ))
+            }
+        };
+        Ok(result)
+    }
+
+    /// Answers a single request, or nothing for a notification.
+    fn call(&mut self, request: &Value) -> Option<Value> {
+        let result = match (&request["jsonrpc"], &request["method"], &request["params"]) {
+            (Value::String(version), Value::String(method), params) if version == "2.0" => {
+                match params {
+                    Value::Object(_) => self.dispatch(method, params),
+                    Value::Null => self.dispatch(method, &json!({})),
+                    _ => Err(RpcError::params("Parameters must be passed by name")),
+                }
+            }
+            _ => Err(RpcError::new(INVALID_REQUEST, "Invalid request")),
+        };
+        let id = match request.get("id") {
+            Some(id) => id.clone(),
+            None if request["jsonrpc"] == "2.0" && request["method"].is_string() => return None,
+            None => Value::Null,
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": e.code, "message": e.message },
+            }),
+        })
+    }
+
+    /// Answers a line holding a request or a batch of them.
+    pub fn handle(&mut self, line: &str) -> Option<String> {
+        let response = match serde_json::from_str::<Value>(line) {
+            Err(e) => Some(json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": PARSE_ERROR, "message": e.to_string() },
+            })),
+            Ok(Value::Array(requests)) if !requests.is_empty() => {
+                let responses: Vec<Value> = requests.iter().filter_map(|r| self.call(r)).collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(request) => self.call(&request),
+        };
+        response.map(|r| r.to_string())
+    }
+
+    /// Answers requests until the end of `input`, skipping blank lines.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> Result<(), String> {
+        for line in input.lines() {
+            let line = line.map_err(|e| e.to_string())?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle(&line) {
+                writeln!(output, "{}", response).map_err(|e| e.to_string())?;
+                output.flush().map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(session: &mut Session, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = session.handle(&request.to_string()).unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn test_session() {
+        let mut session = Session::default();
+        let loaded = call(
+            &mut session,
+            "load",
+            json!({ "name": "a", "text": "1.3434.22143.321" }),
+        );
+        assert_eq!(loaded, json!({ "jsonrpc": "2.0", "id": 1, "result": null }));
+        let board = json!([[null]]);
+        call(&mut session, "load", json!({ "name": "b", "board": board }));
+        assert!(call(
+            &mut session,
+            "load",
+            json!({ "name": "c", "board": [[1, 2]] })
+        )["error"]
+            .is_object());
+        assert_eq!(
+            call(&mut session, "list", json!({}))["result"],
+            json!(["a", "b"])
+        );
+
+        let result =
+            |session: &mut Session, method, params| call(session, method, params)["result"].clone();
+        assert_eq!(
+            result(
+                &mut session,
+                "candidates",
+                json!({ "name": "a", "cell": "r1c2" })
+            ),
+            json!([2])
+        );
+        assert_eq!(
+            result(&mut session, "hint", json!({ "name": "a" })),
+            json!({ "cell": { "row": 1, "col": 2 }, "value": 2 })
+        );
+        result(
+            &mut session,
+            "set",
+            json!({ "name": "a", "cell": { "row": 1, "col": 2 }, "value": 2 }),
+        );
+        assert_eq!(
+            result(&mut session, "get", json!({ "name": "a" }))[0],
+            json!([1, 2, 3, 4])
+        );
+        assert_eq!(
+            result(&mut session, "solve", json!({ "name": "a" })),
+            json!([[1, 2, 3, 4], [3, 4, 1, 2], [2, 1, 4, 3], [4, 3, 2, 1]])
+        );
+        result(
+            &mut session,
+            "set",
+            json!({ "name": "a", "cell": "r2c3", "value": 4, "check": false }),
+        );
+        let validation = result(&mut session, "validate", json!({ "name": "a" }));
+        assert_eq!(validation["valid"], json!(false));
+        let drawing = result(&mut session, "render", json!({ "board": board }));
+        assert_eq!(drawing, json!(Board::from_json(&board).unwrap().draw()));
+        assert!(result(
+            &mut session,
+            "render",
+            json!({ "name": "a", "format": "svg" })
+        )
+        .as_str()
+        .unwrap()
+        .starts_with("<svg"));
+        result(&mut session, "unload", json!({ "name": "b" }));
+        assert_eq!(result(&mut session, "list", Value::Null), json!(["a"]));
+    }
+
+    #[test]
+    fn test_session_errors() {
+        let mut session = Session::default();
+        let code = |session: &mut Session, line: &str| {
+            let response: Value = serde_json::from_str(&session.handle(line).unwrap()).unwrap();
+            response["error"]["code"].clone()
+        };
+        assert_eq!(code(&mut session, "{"), json!(PARSE_ERROR));
+        assert_eq!(
+            code(&mut session, r#"{"id": 1, "method": "list"}"#),
+            json!(INVALID_REQUEST)
+        );
+        let batch: Value = serde_json::from_str(&session.handle("[1]").unwrap()).unwrap();
+        assert_eq!(batch[0]["error"]["code"], json!(INVALID_REQUEST));
+        assert_eq!(
+            code(
+                &mut session,
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "fly"}"#
+            ),
+            json!(METHOD_NOT_FOUND)
+        );
+        assert_eq!(
+            code(
+                &mut session,
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "get", "params": [1]}"#
+            ),
+            json!(INVALID_PARAMS)
+        );
+        assert_eq!(
+            code(
+                &mut session,
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "get", "params": {"name": "x"}}"#
+            ),
+            json!(FAILED)
+        );
+        session.handle(r#"{"jsonrpc": "2.0", "method": "load", "params": {"name": "a", "text": "1.3434.22143.321"}}"#);
+        for value in [0, 9] {
+            let line = format!(
+                r#"{{"jsonrpc": "2.0", "id": 1, "method": "set", "params": {{"name": "a", "cell": "r1c2", "value": {}, "check": false}}}}"#,
+                value
+            );
+            assert_eq!(code(&mut session, &line), json!(INVALID_PARAMS));
+        }
+        let mut impatient = Session::new(Duration::ZERO);
+        assert_eq!(
+            code(
+                &mut impatient,
+                r#"{"jsonrpc": "2.0", "id": 1, "method": "solve", "params": {"board": [[null]]}}"#
+            ),
+            json!(FAILED)
+        );
+        assert_eq!(
+            session.handle(r#"{"jsonrpc": "2.0", "method": "list"}"#),
+            None
+        );
+        assert_eq!(
+            session.handle(r#"{"jsonrpc": "2.0", "method": "fly"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_session_run() {
+        let input = concat!(
+            r#"{"jsonrpc": "2.0", "method": "load", "params": {"name": "a", "text": "1.3434.22143.321"}}"#,
+            "\n\n",
+            r#"[{"jsonrpc": "2.0", "id": 1, "method": "list"}, {"jsonrpc": "2.0", "id": "x", "method": "hint", "params": {"name": "b"}}]"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        Session::default()
+            .run(input.as_bytes(), &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let batch: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(batch[0]["result"], json!(["a"]));
+        assert_eq!(batch[1]["id"], json!("x"));
+        assert_eq!(batch[1]["error"]["message"], json!("Unknown board 'b'"));
+    }
+}
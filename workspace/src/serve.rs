@@ -0,0 +1,251 @@
+use std::io::Read;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use libsudoku::svg::SvgOptions;
+use libsudoku::Board;
+use serde_json::{json, Value};
+use tiny_http::{Header, Request, Response, Server};
+
+const JSON: &str = "application/json";
+
+/// Bounds on the work done for a single request.
+#[derive(Debug, Clone)]
+pub struct Limits {
+    /// Largest accepted request body, in bytes.
+    pub max_body: usize,
+    /// Longest time spent solving, counting or rating a board.
+    pub timeout: Duration,
+    /// Highest `limit` accepted by `/count`.
+    pub max_count: usize,
+    /// Requests answered at the same time, the others wait their turn.
+    pub workers: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_body: 64 * 1024,
+            timeout: Duration::from_secs(2),
+            max_count: 1000,
+            workers: 8,
+        }
+    }
+}
+
+/// Status, content type and body of a response.
+type Reply = (u16, &'static str, String);
+
+fn error(status: u16, message: &str) -> Reply {
+    (status, JSON, json!({ "error": message }).to_string())
+}
+
+/// Maps errors of time bounded searches to `503 Service Unavailable`.
+fn timed<T>(result: Result<T, String>) -> Result<T, Reply> {
+    result.map_err(|e| error(503, &e))
+}
+
+/// Finds `key` in the query string of `url`.
+fn query<'a>(url: &'a str, key: &str) -> Option<&'a str> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(k, v)| (k == key).then_some(v))
+}
+
+/// Answers a request with a board as its JSON body, the same array of rows
+/// `From<&str> for Board` reads.
+pub fn route(method: &str, url: &str, body: &str, limits: &Limits) -> Reply {
+    let path = url.split('?').next().unwrap_or_default();
+    if !["/solve", "/validate", "/rate", "/count", "/render"].contains(&path) {
+        return error(404, &format!("Unknown endpoint '{}'", path));
+    }
+    if method != "POST" {
+        return error(405, "Only POST is supported");
+    }
+    // Givens repeating a peer are reported by `/validate`, or as unsolvable.
+    let board = match serde_json::from_str::<Value>(body)
+        .map_err(|e| e.to_string())
+        .and_then(|value| Board::from_json_unchecked(&value))
+    {
+        Ok(board) => board,
+        Err(e) => return error(400, &e),
+    };
+    match reply(path, url, &board, limits) {
+        Ok(reply) | Err(reply) => reply,
+    }
+}
+
+fn reply(path: &str, url: &str, board: &Board, limits: &Limits) -> Result<Reply, Reply> {
+    let body = match path {
+        "/solve" => match timed(board.solve_within(limits.timeout))? {
+            Some(solution) => serde_json::to_string(&solution).unwrap(),
+            None => return Err(error(422, "Puzzle has no solution")),
+        },
+        "/validate" => {
+            let conflicts = board.conflicts();
+            json!({
+                "valid": conflicts.is_empty(),
+                "solved": board.is_solved(),
+                "conflicts": conflicts,
+            })
+            .to_string()
+        }
+        "/rate" => {
+            let deadline = Instant::now() + limits.timeout;
+            if timed(board.count_solutions_within(1, limits.timeout))? == 0 {
+                return Err(error(422, "Puzzle has no solution"));
+            }
+            let left = deadline.saturating_duration_since(Instant::now());
+            serde_json::to_string(&timed(board.rate_within(left))?).unwrap()
+        }
+        "/count" => {
+            let limit = match query(url, "limit").map(str::parse::<usize>) {
+                None => 2,
+                Some(Ok(limit)) if (1..=limits.max_count).contains(&limit) => limit,
+                Some(_) => {
+                    let message = format!("limit must be between 1 and {}", limits.max_count);
+                    return Err(error(400, &message));
+                }
+            };
+            let solutions = timed(board.count_solutions_within(limit, limits.timeout))?;
+            json!({ "solutions": solutions, "limit": limit }).to_string()
+        }
+        _ => {
+            return match query(url, "format").unwrap_or("text") {
+                "text" => Ok((200, "text/plain; charset=utf-8", board.draw())),
+                "svg" => Ok((200, "image/svg+xml", board.to_svg(&SvgOptions::default()))),
+                other => Err(error(400, &format!("Unknown render format '{}'", other))),
+            }
+        }
+    };
+    Ok((200, JSON, body))
+}
+
+fn handle(mut request: Request, limits: &Limits) {
+    let mut body = String::new();
+    let read = request
+        .as_reader()
+        .take(limits.max_body as u64 + 1)
+        .read_to_string(&mut body);
+    let (status, content_type, text) = match read {
+        Err(e) => error(400, &e.to_string()),
+        Ok(n) if n > limits.max_body => error(
+            413,
+            &format!("Request body exceeds {} bytes", limits.max_body),
+        ),
+        Ok(_) => route(request.method().as_str(), request.url(), &body, limits),
+    };
+    let header = Header::from_bytes("Content-Type", content_type).unwrap();
+    let response = Response::from_string(text)
+        .with_status_code(status)
+        .with_header(header);
+    let _ = request.respond(response);
+}
+
+/// Starts listening on localhost, on a free port when `port` is 0.
+pub fn listen(port: u16) -> Result<Server, String> {
+    Server::http(("127.0.0.1", port)).map_err(|e| e.to_string())
+}
+
+/// Answers requests forever on `limits.workers` threads.
+pub fn serve(server: Server, limits: Limits) {
+    thread::scope(|scope| {
+        for _ in 0..limits.workers.max(1) {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    handle(request, &limits);
+                }
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpStream;
+
+    use super::*;
+
+    const PUZZLE: &str = "[[1,null,3,4],[3,4,null,2],[2,1,4,3],[null,3,2,1]]";
+    const BROKEN: &str =
+        "[[1,1,null,null],[null,null,null,null],[null,null,null,null],[null,null,null,null]]";
+
+    fn post(url: &str, body: &str) -> Reply {
+        route("POST", url, body, &Limits::default())
+    }
+
+    #[test]
+    fn test_route() {
+        let (status, _, body) = post("/solve", PUZZLE);
+        assert_eq!(status, 200);
+        assert_eq!(body, "[[1,2,3,4],[3,4,1,2],[2,1,4,3],[4,3,2,1]]");
+
+        let (_, _, body) = post("/validate", BROKEN);
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["valid"], json!(false));
+        assert_eq!(value["conflicts"][1], json!({"row": 1, "col": 2}));
+
+        let (_, _, body) = post("/rate", PUZZLE);
+        assert!(body.contains("\"difficulty\":\"easy\""), "{}", body);
+        assert_eq!(
+            post("/count?limit=5", "[[null]]").2,
+            r#"{"limit":5,"solutions":1}"#
+        );
+        assert_eq!(post("/render", PUZZLE).2, Board::from(PUZZLE).draw());
+        assert_eq!(post("/render?format=svg", PUZZLE).1, "image/svg+xml");
+    }
+
+    #[test]
+    fn test_route_errors() {
+        assert_eq!(post("/fly", PUZZLE).0, 404);
+        assert_eq!(route("GET", "/solve", PUZZLE, &Limits::default()).0, 405);
+        assert_eq!(post("/solve", "[[1,2]").0, 400);
+        assert_eq!(post("/solve", "[[1,1],[null,null]]").0, 400);
+        assert_eq!(post("/solve", BROKEN).0, 422);
+        assert_eq!(post("/rate", BROKEN).0, 422);
+        assert_eq!(post("/count?limit=0", PUZZLE).0, 400);
+        assert_eq!(post("/render?format=pdf", PUZZLE).0, 400);
+
+        let limits = Limits {
+            timeout: Duration::ZERO,
+            ..Limits::default()
+        };
+        let (status, _, body) = route("POST", "/solve", PUZZLE, &limits);
+        assert_eq!(status, 503);
+        assert!(body.contains("took longer"));
+    }
+
+    #[test]
+    fn test_serve() {
+        let server = listen(0).unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        let limits = Limits {
+            max_body: 64,
+            workers: 1,
+            ..Limits::default()
+        };
+        thread::spawn(move || serve(server, limits));
+
+        let request = |body: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(
+                stream,
+                "POST /solve HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = request(PUZZLE);
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.ends_with("[[1,2,3,4],[3,4,1,2],[2,1,4,3],[4,3,2,1]]"));
+        let response = request(&format!("{:65}", PUZZLE));
+        assert!(response.starts_with("HTTP/1.1 413"), "{}", response);
+    }
+}
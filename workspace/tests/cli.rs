@@ -0,0 +1,163 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+const PUZZLE: &str = "1.3434.22143.321\n";
+
+fn sudoker() -> Command {
+    Command::cargo_bin("workspace").unwrap()
+}
+
+#[test]
+fn test_draw_plain() {
+    sudoker()
+        .args(["draw", "-"])
+        .write_stdin(PUZZLE)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("╔═══════╦═══════╗\n║ 1 │ ■ ║"))
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn test_draw_colors() {
+    sudoker()
+        .args(["draw", "-", "--solve", "--color", "always"])
+        .write_stdin(PUZZLE)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "║\x1b[1m 1 \x1b[0m│\x1b[32m 2 \x1b[0m║",
+        ));
+}
+
+#[test]
+fn test_draw_select() {
+    sudoker()
+        .args(["draw", "-", "--color=always", "--select", "r1c2"])
+        .write_stdin(PUZZLE)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[7m ■ \x1b[0m"));
+}
+
+#[test]
+fn test_draw_stats() {
+    sudoker()
+        .args(["draw", "-", "--solve", "--stats"])
+        .write_stdin(PUZZLE)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("╔═══════╦═══════╗\n║ 1 │ 2 ║"))
+        .stderr(
+            predicate::str::is_match(
+                "^nodes: 3, backtracks: 0, max depth: 3, propagations: 6, time: [0-9.]+ms\n$",
+            )
+            .unwrap(),
+        );
+    sudoker()
+        .args(["draw", "-", "--stats"])
+        .write_stdin(PUZZLE)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_draw_errors() {
+    sudoker()
+        .args(["draw", "missing.sdk"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::starts_with("error: missing.sdk"));
+    sudoker()
+        .args(["draw", "-", "--select", "x1"])
+        .write_stdin(PUZZLE)
+        .assert()
+        .failure();
+    sudoker()
+        .args(["draw", "-", "--solve"])
+        .write_stdin("11..............")
+        .assert()
+        .failure()
+        .stderr("error: Puzzle has no solution\n");
+}
+
+#[test]
+fn test_play_errors() {
+    sudoker()
+        .args(["play", "missing.sdk"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::starts_with("error: missing.sdk"));
+    sudoker()
+        .args(["play", "-"])
+        .write_stdin("1234")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_repl_script() {
+    let file = format!("sudoker-repl-{}.txt", std::process::id());
+    let path = std::env::temp_dir().join(file);
+    std::fs::write(&path, PUZZLE).unwrap();
+    sudoker()
+        .args(["repl"])
+        .write_stdin(format!(
+            "load {}\nset r1c2 2\ncand r2c3\nsolve\ndraw\n",
+            path.display()
+        ))
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("1\n╔═══════╦═══════╗\n║ 1 │ 2 ║"));
+    sudoker()
+        .args(["repl"])
+        .arg(&path)
+        .write_stdin("set r1c2 3\nundo\n")
+        .assert()
+        .failure()
+        .stderr(
+            "error: line 1: '3' cannot be inserted in r1c2\n\
+             error: line 2: Nothing to undo\n\
+             error: 2 commands failed\n",
+        );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_rpc() {
+    sudoker()
+        .args(["rpc"])
+        .write_stdin(concat!(
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "load", "params": {"name": "p", "text": "1.3434.22143.321"}}"#,
+            "\n",
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "candidates", "params": {"name": "p", "cell": "r4c1"}}"#,
+            "\n",
+        ))
+        .assert()
+        .success()
+        .stdout(concat!(
+            r#"{"id":1,"jsonrpc":"2.0","result":null}"#,
+            "\n",
+            r#"{"id":2,"jsonrpc":"2.0","result":[4]}"#,
+            "\n",
+        ));
+}
+
+#[test]
+fn test_batch() {
+    sudoker()
+        .args(["batch", "-", "--threads", "2"])
+        .write_stdin("1.3434.22143.321\n\n11..............\n12\n[[null]]\n")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(concat!(
+                "^1\tsolved\t\\d+\t1234341221434321\n",
+                "3\tunsolvable\t\\d+\t\n",
+                "4\tinvalid\t\\d+\t.+\n",
+                "5\tsolved\t\\d+\t1\n$",
+            ))
+            .unwrap(),
+        )
+        .stderr(predicate::str::is_match("^4 puzzles in [0-9.]+s: 2 solved, 1 unsolvable, 0 multiple, 1 invalid\n$").unwrap());
+}
@@ -0,0 +1,186 @@
+//! WebAssembly bindings of `libsudoku`, built with `wasm-pack build` into a
+//! JavaScript package with TypeScript definitions.
+//!
+//! Failures are thrown as JavaScript `Error`s carrying the library message.
+
+use libsudoku::formats::{Format, Puzzle};
+use libsudoku::loc::{Loc, L};
+use libsudoku::svg::SvgOptions;
+use wasm_bindgen::prelude::*;
+
+/// Cell of a board, counted from 1.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub row: L,
+    pub col: L,
+}
+
+impl From<Loc> for Cell {
+    fn from(other: Loc) -> Self {
+        Self {
+            row: other.row,
+            col: other.col,
+        }
+    }
+}
+
+/// Digit that can be placed next, see `Board.hint`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hint {
+    pub row: L,
+    pub col: L,
+    pub value: L,
+}
+
+/// Sudoku board.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Board {
+    board: libsudoku::Board,
+}
+
+impl Board {
+    fn parse(text: &str) -> Result<Self, String> {
+        let puzzle = Puzzle::read(text)?;
+        Ok(Self {
+            board: puzzle.state.unwrap_or(puzzle.board),
+        })
+    }
+
+    fn loc(&self, row: L, col: L) -> Result<Loc, String> {
+        let loc = Loc::new(row, col);
+        match self.board.contains_key(&loc) {
+            true => Ok(loc),
+            false => Err(format!("Cell r{}c{} is outside of the board", row, col)),
+        }
+    }
+
+    fn try_set(&mut self, row: L, col: L, value: Option<L>) -> Result<(), String> {
+        let loc = self.loc(row, col)?;
+        self.board.try_set(&loc, value)
+    }
+
+    fn try_render(&self, format: &str) -> Result<String, String> {
+        match format {
+            "text" => Ok(self.board.draw()),
+            "svg" => Ok(self.board.to_svg(&SvgOptions::default())),
+            name => Puzzle::from(self.board.clone()).write(name.parse::<Format>()?),
+        }
+    }
+}
+
+fn js(error: String) -> JsError {
+    JsError::new(&error)
+}
+
+#[wasm_bindgen]
+impl Board {
+    /// Reads a puzzle in any supported format, detected from its content.
+    #[wasm_bindgen(constructor)]
+    pub fn new(text: &str) -> Result<Board, JsError> {
+        Self::parse(text).map_err(js)
+    }
+
+    /// Number of rows, which is also the highest digit.
+    #[wasm_bindgen(getter)]
+    pub fn top(&self) -> L {
+        self.board.top()
+    }
+
+    /// Digit of a cell, or `undefined` for an empty cell.
+    pub fn get(&self, row: L, col: L) -> Result<Option<L>, JsError> {
+        Ok(self.board.value(&self.loc(row, col).map_err(js)?))
+    }
+
+    /// Places a digit following the rules, or clears the cell for `undefined`.
+    pub fn set(&mut self, row: L, col: L, value: Option<L>) -> Result<(), JsError> {
+        self.try_set(row, col, value).map_err(js)
+    }
+
+    /// Digits that can be placed in a cell without breaking the rules.
+    pub fn candidates(&self, row: L, col: L) -> Result<Vec<L>, JsError> {
+        Ok(self
+            .board
+            .available_values(&self.loc(row, col).map_err(js)?))
+    }
+
+    /// Solved copy of the board, or `undefined` without a solution.
+    pub fn solve(&self) -> Option<Board> {
+        self.board.solve().map(|board| Self { board })
+    }
+
+    #[wasm_bindgen(js_name = countSolutions)]
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.board.count_solutions(limit)
+    }
+
+    #[wasm_bindgen(js_name = isSolved)]
+    pub fn is_solved(&self) -> bool {
+        self.board.is_solved()
+    }
+
+    /// Filled cells holding the same digit as one of their peers.
+    pub fn conflicts(&self) -> Vec<Cell> {
+        self.board.conflicts().into_iter().map(Cell::from).collect()
+    }
+
+    /// Next digit to place, or `undefined` when the board has no solution.
+    pub fn hint(&self) -> Option<Hint> {
+        self.board.hint().map(|(loc, value)| Hint {
+            row: loc.row,
+            col: loc.col,
+            value,
+        })
+    }
+
+    /// Renders the board as `text`, `svg` or one of the puzzle formats.
+    pub fn render(&self, format: &str) -> Result<String, JsError> {
+        self.try_render(format).map_err(js)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "1.3434.22143.321";
+
+    #[test]
+    fn test_board() {
+        let mut board = Board::parse(PUZZLE).unwrap();
+        assert_eq!(board.top(), 4);
+        assert_eq!(board.candidates(1, 2).ok(), Some(vec![2]));
+        assert_eq!(board.hint().map(|h| h.value), Some(2));
+        board.set(1, 2, Some(2)).ok();
+        assert_eq!(board.get(1, 2).ok(), Some(Some(2)));
+
+        let solution = board.solve().unwrap();
+        assert!(solution.is_solved() && !board.is_solved());
+        assert_eq!(solution.count_solutions(2), 1);
+        assert_eq!(
+            solution.render("line").ok().as_deref(),
+            Some("1234341221434321\n")
+        );
+        assert!(solution.render("text").ok().unwrap().starts_with('╔'));
+        assert_eq!(
+            solution.render("json").ok().as_deref(),
+            Some("[[1,2,3,4],[3,4,1,2],[2,1,4,3],[4,3,2,1]]\n")
+        );
+    }
+
+    #[test]
+    fn test_board_errors() {
+        assert!(Board::parse("12").is_err());
+        let mut board = Board::parse("11..............").unwrap();
+        assert_eq!(
+            board.conflicts(),
+            [Cell { row: 1, col: 1 }, Cell { row: 1, col: 2 }]
+        );
+        assert!(board.solve().is_none() && board.hint().is_none());
+        assert!(board.loc(5, 1).is_err());
+        assert!(board.try_set(2, 1, Some(1)).is_err());
+        assert!(board.try_render("pdf").is_err());
+    }
+}